@@ -0,0 +1,4 @@
+-- Add migration script here
+ALTER TABLE todos ADD COLUMN IF NOT EXISTS deleted_at timestamptz;
+
+CREATE INDEX idx_user_deleted_at ON todos (user_id, deleted_at);
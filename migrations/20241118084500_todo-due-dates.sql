@@ -0,0 +1,5 @@
+-- Add migration script here
+ALTER TABLE todos ADD COLUMN IF NOT EXISTS start_at timestamptz;
+ALTER TABLE todos ADD COLUMN IF NOT EXISTS due_at timestamptz;
+
+CREATE INDEX idx_user_due_at ON todos (user_id, due_at);
@@ -0,0 +1,4 @@
+-- Add migration script here
+ALTER TABLE todos ADD COLUMN IF NOT EXISTS priority SMALLINT NOT NULL DEFAULT 0;
+
+CREATE INDEX idx_user_priority ON todos (user_id, priority);
@@ -0,0 +1,30 @@
+-- Add migration script here
+CREATE TABLE IF NOT EXISTS projects (
+    id bigserial PRIMARY KEY,
+    user_id bigint NOT NULL REFERENCES users(id),
+    name VARCHAR(128) NOT NULL,
+    description text NOT NULL DEFAULT '',
+    color VARCHAR(16) NOT NULL DEFAULT '',
+    archived boolean NOT NULL DEFAULT false,
+    position integer NOT NULL DEFAULT 0,
+    is_inbox boolean NOT NULL DEFAULT false,
+    created_at timestamptz DEFAULT CURRENT_TIMESTAMP,
+    updated_at timestamptz DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_projects_user_position ON projects (user_id, position);
+CREATE UNIQUE INDEX idx_projects_user_inbox ON projects (user_id) WHERE is_inbox;
+
+CREATE TRIGGER trg_projects_updated_at BEFORE UPDATE ON projects
+    FOR EACH ROW EXECUTE FUNCTION set_updated_at();
+
+ALTER TABLE todos ADD COLUMN IF NOT EXISTS project_id bigint REFERENCES projects(id) ON DELETE SET NULL;
+
+CREATE INDEX idx_user_project ON todos (user_id, project_id);
+
+-- every existing user gets an Inbox holding the todos they already have
+INSERT INTO projects (user_id, name, is_inbox) SELECT id, 'Inbox', true FROM users;
+
+ALTER TABLE todos DISABLE TRIGGER trg_todos_updated_at;
+UPDATE todos SET project_id = p.id FROM projects p WHERE p.user_id = todos.user_id AND p.is_inbox;
+ALTER TABLE todos ENABLE TRIGGER trg_todos_updated_at;
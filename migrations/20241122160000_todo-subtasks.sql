@@ -0,0 +1,5 @@
+-- Add migration script here
+ALTER TABLE todos ADD COLUMN IF NOT EXISTS parent_id bigint REFERENCES todos(id) ON DELETE CASCADE;
+ALTER TABLE todos ADD COLUMN IF NOT EXISTS auto_complete boolean NOT NULL DEFAULT false;
+
+CREATE INDEX idx_todos_parent_id ON todos (parent_id);
@@ -0,0 +1,2 @@
+-- Add migration script here
+ALTER TABLE todos ADD COLUMN IF NOT EXISTS recurrence text;
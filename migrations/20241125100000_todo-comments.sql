@@ -0,0 +1,24 @@
+-- Add migration script here
+CREATE TABLE IF NOT EXISTS todo_comments (
+    id bigserial PRIMARY KEY,
+    todo_id bigint NOT NULL REFERENCES todos(id) ON DELETE CASCADE,
+    user_id bigint NOT NULL REFERENCES users(id),
+    body text NOT NULL,
+    created_at timestamptz DEFAULT CURRENT_TIMESTAMP,
+    updated_at timestamptz DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_todo_comments_todo_id ON todo_comments (todo_id, id);
+
+CREATE TRIGGER trg_todo_comments_updated_at BEFORE UPDATE ON todo_comments
+    FOR EACH ROW EXECUTE FUNCTION set_updated_at();
+
+-- the body a comment had before each edit
+CREATE TABLE IF NOT EXISTS todo_comment_revisions (
+    id bigserial PRIMARY KEY,
+    comment_id bigint NOT NULL REFERENCES todo_comments(id) ON DELETE CASCADE,
+    body text NOT NULL,
+    edited_at timestamptz DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_todo_comment_revisions_comment_id ON todo_comment_revisions (comment_id);
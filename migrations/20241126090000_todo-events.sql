@@ -0,0 +1,13 @@
+-- Add migration script here
+CREATE TABLE IF NOT EXISTS todo_events (
+    id bigserial PRIMARY KEY,
+    todo_id bigint NOT NULL REFERENCES todos(id) ON DELETE CASCADE,
+    actor_id bigint NOT NULL REFERENCES users(id),
+    action SMALLINT NOT NULL,
+    field VARCHAR(32),
+    old_value jsonb,
+    new_value jsonb,
+    created_at timestamptz DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_todo_events_todo_id ON todo_events (todo_id, id);
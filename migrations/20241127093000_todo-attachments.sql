@@ -0,0 +1,14 @@
+-- Add migration script here
+CREATE TABLE IF NOT EXISTS todo_attachments (
+    id bigserial PRIMARY KEY,
+    todo_id bigint NOT NULL REFERENCES todos(id) ON DELETE CASCADE,
+    user_id bigint NOT NULL REFERENCES users(id),
+    filename VARCHAR(255) NOT NULL,
+    content_type VARCHAR(127) NOT NULL,
+    size bigint NOT NULL,
+    storage_key VARCHAR(255) NOT NULL UNIQUE,
+    created_at timestamptz DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_todo_attachments_todo_id ON todo_attachments (todo_id, id);
+CREATE INDEX idx_todo_attachments_user_id ON todo_attachments (user_id);
@@ -0,0 +1,28 @@
+-- Add migration script here
+-- reordering is not an edit, moving a todo leaves updated_at alone. Columns
+-- added to todos later have to be listed here too.
+DROP TRIGGER IF EXISTS trg_todos_updated_at ON todos;
+CREATE TRIGGER trg_todos_updated_at
+    BEFORE UPDATE OF user_id, project_id, parent_id, title, description, status, priority,
+        start_at, due_at, recurrence, auto_complete, deleted_at
+    ON todos
+    FOR EACH ROW EXECUTE FUNCTION set_updated_at();
+
+-- rank of the todo within its project, see models::rank
+ALTER TABLE todos ADD COLUMN IF NOT EXISTS position text COLLATE "C";
+
+-- existing todos keep their creation order, spaced out so they can be moved.
+-- The digits of a list are as many as its largest rank needs, so they all
+-- compare as fractions of the same length.
+UPDATE todos t SET position = rtrim(lpad(to_hex(r.n * 4096), length(to_hex(r.total * 4096)), '0'), '0')
+FROM (
+    SELECT id,
+        row_number() OVER (PARTITION BY user_id, project_id ORDER BY id) AS n,
+        count(*) OVER (PARTITION BY user_id, project_id) AS total
+    FROM todos
+) r
+WHERE t.id = r.id;
+
+ALTER TABLE todos ALTER COLUMN position SET NOT NULL;
+
+CREATE INDEX idx_todos_position ON todos (user_id, project_id, position, id);
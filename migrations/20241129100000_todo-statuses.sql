@@ -0,0 +1,27 @@
+-- Add migration script here
+CREATE TABLE IF NOT EXISTS todo_statuses (
+    user_id bigint NOT NULL REFERENCES users(id),
+    id smallint NOT NULL,
+    name VARCHAR(64) NOT NULL,
+    color VARCHAR(16) NOT NULL DEFAULT '',
+    position integer NOT NULL DEFAULT 0,
+    is_done boolean NOT NULL DEFAULT false,
+    -- statuses a todo may move on to, any of them when NULL
+    transitions smallint[],
+    created_at timestamptz DEFAULT CURRENT_TIMESTAMP,
+    updated_at timestamptz DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (user_id, id),
+    UNIQUE (user_id, name)
+);
+
+CREATE TRIGGER trg_todo_statuses_updated_at BEFORE UPDATE ON todo_statuses
+    FOR EACH ROW EXECUTE FUNCTION set_updated_at();
+
+-- every existing user gets the built-in statuses, with the ids todos already use
+INSERT INTO todo_statuses (user_id, id, name, position, is_done)
+SELECT u.id, s.id, s.name, s.id, s.id = 3
+FROM users u
+CROSS JOIN (VALUES (0, 'Pending'), (1, 'Ready'), (2, 'InProgress'), (3, 'Done')) AS s(id, name);
+
+ALTER TABLE todos ADD CONSTRAINT fk_todos_status
+    FOREIGN KEY (user_id, status) REFERENCES todo_statuses (user_id, id);
@@ -0,0 +1,11 @@
+-- Add migration script here
+-- todo_id can't start until blocker_id is done
+CREATE TABLE IF NOT EXISTS todo_dependencies (
+    todo_id bigint NOT NULL REFERENCES todos(id) ON DELETE CASCADE,
+    blocker_id bigint NOT NULL REFERENCES todos(id) ON DELETE CASCADE,
+    created_at timestamptz DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (todo_id, blocker_id),
+    CHECK (todo_id <> blocker_id)
+);
+
+CREATE INDEX idx_todo_dependencies_blocker_id ON todo_dependencies (blocker_id);
@@ -0,0 +1,16 @@
+-- Add migration script here
+CREATE TABLE IF NOT EXISTS refresh_tokens (
+    id bigserial PRIMARY KEY,
+    user_id bigint NOT NULL REFERENCES users(id),
+    -- id of the token obtained at sign in, shared by all of its rotations
+    family_id bigint NOT NULL,
+    -- SHA-256 of the token, hex encoded
+    token_hash CHAR(64) NOT NULL UNIQUE,
+    expires_at timestamptz NOT NULL,
+    -- set once the token has been exchanged for a new one
+    used_at timestamptz,
+    revoked_at timestamptz,
+    created_at timestamptz DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_refresh_tokens_family_id ON refresh_tokens (family_id);
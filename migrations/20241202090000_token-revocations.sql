@@ -0,0 +1,13 @@
+-- Add migration script here
+CREATE TABLE IF NOT EXISTS token_revocations (
+    id bigserial PRIMARY KEY,
+    user_id bigint NOT NULL REFERENCES users(id),
+    -- jti of the revoked token, every token of the user issued up to
+    -- revoked_at when NULL
+    jti VARCHAR(64) UNIQUE,
+    revoked_at timestamptz NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    -- once past, the revoked tokens have expired anyway
+    expires_at timestamptz NOT NULL
+);
+
+CREATE INDEX idx_token_revocations_expires_at ON token_revocations (expires_at);
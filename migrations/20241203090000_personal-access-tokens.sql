@@ -0,0 +1,18 @@
+-- Add migration script here
+CREATE TABLE IF NOT EXISTS personal_access_tokens (
+    id bigserial PRIMARY KEY,
+    user_id bigint NOT NULL REFERENCES users(id),
+    name VARCHAR(64) NOT NULL,
+    -- SHA-256 of the token, hex encoded
+    token_hash CHAR(64) NOT NULL UNIQUE,
+    -- first characters of the token, enough for the user to tell them apart
+    token_hint VARCHAR(16) NOT NULL,
+    scopes VARCHAR(32)[] NOT NULL,
+    -- NULL for a token that never expires
+    expires_at timestamptz,
+    last_used_at timestamptz,
+    revoked_at timestamptz,
+    created_at timestamptz DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_personal_access_tokens_user_id ON personal_access_tokens (user_id);
@@ -1,49 +1,147 @@
 use std::ops::Deref;
 
 use anyhow::Result;
+use chrono::{DateTime, Utc};
 use jwt_simple::prelude::*;
+use rand_core::{OsRng, RngCore};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 
-use crate::{errors::AppError, models::user::User};
+use crate::{config::AuthConfig, errors::AppError, models::user::User};
+
+mod revocation;
+
+pub use revocation::RevocationList;
 
-const JWT_DURATION: u64 = 60 * 60 * 24 * 7;
 const JWT_ISS: &str = "todolist-server";
 const JWT_AUD: &str = "todolist-client";
+const CURSOR_DURATION: u64 = 60 * 60 * 24;
+/// Pagination cursors are signed with the same key as user tokens, the
+/// audience keeps one from being accepted as the other.
+const CURSOR_AUD: &str = "todolist-cursor";
+/// Start of every personal access token, tells them apart from JWTs and lets
+/// secret scanners spot them.
+pub const PERSONAL_TOKEN_PREFIX: &str = "tdl_pat_";
+
+/// Custom claims of an access token.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccessClaims {
+    #[serde(flatten)]
+    pub user: User,
+    /// Issue time in milliseconds, `iat` only has whole seconds which can't
+    /// tell a token issued right after signing out everywhere from one issued
+    /// right before.
+    #[serde(default)]
+    pub iat_ms: i64,
+    /// Family of the refresh token issued along, signing out revokes it with
+    /// the access token.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub family_id: Option<i64>,
+}
+
+impl AccessClaims {
+    pub fn issued_at(&self) -> DateTime<Utc> {
+        DateTime::from_timestamp_millis(self.iat_ms).unwrap_or_default()
+    }
+}
 
 pub struct Authorization {
     pub encode_key: EncodingKey,
     pub decode_key: DecodingKey,
+    /// Lifetime of access tokens, in seconds.
+    pub access_ttl: u64,
+    /// Lifetime of refresh tokens, in seconds.
+    pub refresh_ttl: u64,
 }
 
 impl Authorization {
-    pub fn new(encode_pem: &str, decode_pem: &str) -> Result<Self, AppError> {
-        let enc_key = EncodingKey::load(encode_pem)?;
-        let dec_key = DecodingKey::load(decode_pem)?;
+    pub fn new(conf: &AuthConfig) -> Result<Self, AppError> {
+        let enc_key = EncodingKey::load(&conf.private_key)?;
+        let dec_key = DecodingKey::load(&conf.public_key)?;
         Ok(Self {
             encode_key: enc_key,
             decode_key: dec_key,
+            access_ttl: conf.access_token_ttl,
+            refresh_ttl: conf.refresh_token_ttl,
         })
     }
 
-    pub fn sign(&self, user: User) -> Result<String, AppError> {
-        self.encode_key.sign(user)
+    /// Signs a short-lived access token for the user, issued along with a
+    /// refresh token of `family_id`.
+    pub fn sign(&self, user: User, family_id: Option<i64>) -> Result<String, AppError> {
+        self.encode_key
+            .sign(user, family_id, Duration::from_secs(self.access_ttl))
     }
 
     pub fn verify_user(&self, token: &str) -> Result<User, AppError> {
         self.decode_key.verify(token)
     }
+
+    /// All the claims of a valid access token, for when its id or expiry
+    /// matter.
+    pub fn verify_claims(&self, token: &str) -> Result<JWTClaims<AccessClaims>, AppError> {
+        self.decode_key.verify_claims(token)
+    }
+
+    pub fn revocations(&self) -> &RevocationList {
+        &self.decode_key.1
+    }
+
+    /// Signs a pagination cursor into an opaque token.
+    pub fn sign_cursor<T>(&self, cursor: T) -> Result<String, AppError>
+    where
+        T: Serialize + DeserializeOwned,
+    {
+        let claims = Claims::with_custom_claims(cursor, Duration::from_secs(CURSOR_DURATION));
+        let claims = claims.with_issuer(JWT_ISS).with_audience(CURSOR_AUD);
+
+        Ok(self.encode_key.0.sign(claims)?)
+    }
+
+    pub fn verify_cursor<T>(&self, token: &str) -> Result<T, AppError>
+    where
+        T: Serialize + DeserializeOwned,
+    {
+        let options = VerificationOptions {
+            allowed_issuers: Some(HashSet::from([JWT_ISS.to_string()])),
+            allowed_audiences: Some(HashSet::from([CURSOR_AUD.to_string()])),
+            ..Default::default()
+        };
+
+        let claims = self
+            .decode_key
+            .0
+            .verify_token::<T>(token, Some(options))
+            .map_err(|e| AppError::InvalidArgument(format!("invalid cursor: {}", e)))?;
+        Ok(claims.custom)
+    }
 }
 
 pub struct EncodingKey(Ed25519KeyPair);
-pub struct DecodingKey(Ed25519PublicKey);
+/// Also holds the tokens revoked before their expiry.
+pub struct DecodingKey(Ed25519PublicKey, RevocationList);
 
 impl EncodingKey {
     pub fn load(pem: &str) -> Result<Self, AppError> {
         Ok(Self(Ed25519KeyPair::from_pem(pem)?))
     }
 
-    pub fn sign(&self, user: User) -> Result<String, AppError> {
-        let claims = Claims::with_custom_claims(user, Duration::from_secs(JWT_DURATION));
-        let claims = claims.with_issuer(JWT_ISS).with_audience(JWT_AUD);
+    pub fn sign(
+        &self,
+        user: User,
+        family_id: Option<i64>,
+        valid_for: Duration,
+    ) -> Result<String, AppError> {
+        let custom = AccessClaims {
+            user,
+            iat_ms: Utc::now().timestamp_millis(),
+            family_id,
+        };
+        let claims = Claims::with_custom_claims(custom, valid_for);
+        let claims = claims
+            .with_issuer(JWT_ISS)
+            .with_audience(JWT_AUD)
+            .with_jwt_id(random_hex(16));
 
         Ok(self.0.sign(claims)?)
     }
@@ -51,21 +149,69 @@ impl EncodingKey {
 
 impl DecodingKey {
     pub fn load(pem: &str) -> Result<Self, AppError> {
-        Ok(Self(Ed25519PublicKey::from_pem(pem)?))
+        Ok(Self(
+            Ed25519PublicKey::from_pem(pem)?,
+            RevocationList::default(),
+        ))
     }
 
     pub fn verify(&self, token: &str) -> Result<User, AppError> {
+        Ok(self.verify_claims(token)?.custom.user)
+    }
+
+    pub fn verify_claims(&self, token: &str) -> Result<JWTClaims<AccessClaims>, AppError> {
         let options = jwt_simple::common::VerificationOptions {
             allowed_issuers: Some(HashSet::from([JWT_ISS.to_string()])),
             allowed_audiences: Some(HashSet::from([JWT_AUD.to_string()])),
             ..Default::default()
         };
 
-        let claims = self.0.verify_token::<User>(token, Some(options))?;
-        Ok(claims.custom)
+        let claims = self
+            .0
+            .verify_token::<AccessClaims>(token, Some(options))
+            .map_err(|e| AppError::AuthFailed(e.to_string()))?;
+        if self.1.is_revoked(
+            claims.jwt_id.as_deref(),
+            claims.custom.user.id,
+            claims.custom.issued_at(),
+        ) {
+            return Err(AppError::AuthFailed("token revoked".to_string()));
+        }
+
+        Ok(claims)
     }
 }
 
+/// A new opaque refresh token, only its `hash_token` is stored.
+pub fn gen_refresh_token() -> String {
+    random_hex(32)
+}
+
+/// A new personal access token, only its `hash_token` is stored.
+pub fn gen_personal_token() -> String {
+    format!("{}{}", PERSONAL_TOKEN_PREFIX, random_hex(20))
+}
+
+pub fn is_personal_token(token: &str) -> bool {
+    token.starts_with(PERSONAL_TOKEN_PREFIX)
+}
+
+fn random_hex(len: usize) -> String {
+    let mut bytes = vec![0u8; len];
+    OsRng.fill_bytes(&mut bytes);
+
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// SHA-256 of a token, hex encoded. Tokens are random enough that a salt
+/// would add nothing.
+pub fn hash_token(token: &str) -> String {
+    Sha256::digest(token.as_bytes())
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
 impl Deref for EncodingKey {
     type Target = Ed25519KeyPair;
 
@@ -101,9 +247,30 @@ mod tests {
         };
 
         let u = user1.clone();
-        let token = encoding_key.sign(user1)?;
+        let token = encoding_key.sign(user1, None, Duration::from_secs(60))?;
         let user2 = decoding_key.verify(&token)?;
         assert_eq!(u, user2);
         Ok(())
     }
+
+    #[test]
+    fn test_refresh_token() {
+        let token = gen_refresh_token();
+        assert_eq!(token.len(), 64);
+        assert_ne!(token, gen_refresh_token());
+
+        assert_eq!(
+            hash_token("abc"),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+
+    #[test]
+    fn test_personal_token() {
+        let token = gen_personal_token();
+        assert_eq!(token.len(), PERSONAL_TOKEN_PREFIX.len() + 40);
+        assert!(is_personal_token(&token));
+        assert!(!is_personal_token(&gen_refresh_token()));
+        assert_ne!(token, gen_personal_token());
+    }
 }
@@ -0,0 +1,135 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, RwLock},
+};
+
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+
+use crate::{
+    errors::AppError,
+    models::revocation::{self, TokenRevocation},
+};
+
+/// In-process copy of the `token_revocations` table, so checking a token
+/// takes no query. `sync` brings in the revocations made by other instances.
+#[derive(Debug, Clone, Default)]
+pub struct RevocationList {
+    inner: Arc<RwLock<Revocations>>,
+}
+
+#[derive(Debug, Default)]
+struct Revocations {
+    /// Expiry of each revoked token, by jti.
+    tokens: HashMap<String, DateTime<Utc>>,
+    /// Tokens of a user issued up to the first time are revoked, until the
+    /// second.
+    users: HashMap<i64, (DateTime<Utc>, DateTime<Utc>)>,
+}
+
+impl Revocations {
+    fn add(&mut self, revocation: TokenRevocation) {
+        match revocation.jti {
+            Some(jti) => {
+                self.tokens.insert(jti, revocation.expires_at);
+            }
+            None => {
+                let (cutoff, expires_at) = self.users.entry(revocation.user_id).or_default();
+                *cutoff = (*cutoff).max(revocation.revoked_at);
+                *expires_at = (*expires_at).max(revocation.expires_at);
+            }
+        }
+    }
+
+    /// Drops the revocations of tokens that have expired by `now`.
+    fn prune(&mut self, now: DateTime<Utc>) {
+        self.tokens.retain(|_, expires_at| *expires_at > now);
+        self.users.retain(|_, (_, expires_at)| *expires_at > now);
+    }
+}
+
+impl RevocationList {
+    /// Whether the token `jti` of `user_id`, issued at `issued_at`, has been
+    /// revoked.
+    pub fn is_revoked(&self, jti: Option<&str>, user_id: i64, issued_at: DateTime<Utc>) -> bool {
+        let revocations = self.inner.read().expect("revocation lock poisoned");
+        if jti.is_some_and(|jti| revocations.tokens.contains_key(jti)) {
+            return true;
+        }
+
+        revocations
+            .users
+            .get(&user_id)
+            .is_some_and(|(cutoff, _)| issued_at <= *cutoff)
+    }
+
+    pub fn add(&self, revocation: TokenRevocation) {
+        self.inner
+            .write()
+            .expect("revocation lock poisoned")
+            .add(revocation);
+    }
+
+    /// Merges in the revocations in the database and drops those of tokens
+    /// that have expired since. Merging keeps the ones `add`ed while the query
+    /// ran.
+    pub async fn sync(&self, pool: &PgPool) -> Result<(), AppError> {
+        let active = revocation::get_active_revocations(pool).await?;
+        let mut revocations = self.inner.write().expect("revocation lock poisoned");
+        for revocation in active {
+            revocations.add(revocation);
+        }
+        revocations.prune(Utc::now());
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+
+    use super::*;
+
+    #[test]
+    fn test_revocation_list() {
+        let now = Utc::now();
+        let list = RevocationList::default();
+        list.add(TokenRevocation {
+            id: 1,
+            user_id: 1,
+            jti: Some("abc".to_string()),
+            revoked_at: now,
+            expires_at: now + Duration::minutes(15),
+        });
+        assert!(list.is_revoked(Some("abc"), 1, now));
+        assert!(!list.is_revoked(Some("def"), 1, now));
+        assert!(!list.is_revoked(None, 1, now));
+
+        list.add(TokenRevocation {
+            id: 2,
+            user_id: 2,
+            jti: None,
+            revoked_at: now,
+            expires_at: now + Duration::minutes(15),
+        });
+        assert!(list.is_revoked(Some("def"), 2, now - Duration::minutes(1)));
+        assert!(!list.is_revoked(Some("def"), 2, now + Duration::milliseconds(1)));
+        assert!(!list.is_revoked(Some("def"), 1, now - Duration::minutes(1)));
+
+        list.add(TokenRevocation {
+            id: 3,
+            user_id: 3,
+            jti: Some("ghi".to_string()),
+            revoked_at: now,
+            expires_at: now + Duration::seconds(30),
+        });
+        list.inner
+            .write()
+            .unwrap()
+            .prune(now + Duration::minutes(1));
+        assert!(!list.is_revoked(Some("ghi"), 3, now));
+        assert!(list.is_revoked(Some("abc"), 1, now));
+        assert!(list.is_revoked(None, 2, now));
+    }
+}
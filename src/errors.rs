@@ -1,4 +1,7 @@
-use axum::{http::StatusCode, response::IntoResponse};
+use axum::{
+    http::{header, StatusCode},
+    response::IntoResponse,
+};
 use thiserror::Error;
 
 #[derive(Debug, Error)]
@@ -29,11 +32,50 @@ pub enum AppError {
 
     #[error("user not exists: {0}")]
     UserNotFound(String),
+
+    #[error("invalid argument: {0}")]
+    InvalidArgument(String),
+
+    #[error("tag not exists: {0}")]
+    TagNotExists(String),
+
+    #[error("tag name already been used: {0}")]
+    TagAlreadyExists(String),
+
+    #[error("project not exists: {0}")]
+    ProjectNotExists(String),
+
+    #[error("comment not exists: {0}")]
+    CommentNotExists(String),
+
+    #[error("attachment not exists: {0}")]
+    AttachmentNotExists(String),
+
+    #[error("payload too large: {0}")]
+    PayloadTooLarge(String),
+
+    #[error("status not exists: {0}")]
+    StatusNotExists(String),
+
+    #[error("status name already been used: {0}")]
+    StatusAlreadyExists(String),
+
+    #[error("status transition not allowed: {0}")]
+    TransitionNotAllowed(String),
+
+    #[error("todo is blocked: {0}")]
+    TodoBlocked(String),
+
+    #[error("token not exists: {0}")]
+    TokenNotExists(String),
+
+    #[error("insufficient scope: {0}")]
+    InsufficientScope(String),
 }
 
-impl IntoResponse for AppError {
-    fn into_response(self) -> axum::response::Response {
-        let status_code = match self {
+impl AppError {
+    pub fn status_code(&self) -> StatusCode {
+        match self {
             Self::EmailAlreadyExists(_) => StatusCode::BAD_REQUEST,
             Self::AuthFailed(_) => StatusCode::UNAUTHORIZED,
             Self::TomlError(_) => StatusCode::INTERNAL_SERVER_ERROR,
@@ -43,8 +85,44 @@ impl IntoResponse for AppError {
             Self::AnyhowError(_) => StatusCode::INTERNAL_SERVER_ERROR,
             Self::PasswordHashError(_) => StatusCode::INTERNAL_SERVER_ERROR,
             Self::UserNotFound(_) => StatusCode::NOT_FOUND,
+            Self::InvalidArgument(_) => StatusCode::BAD_REQUEST,
+            Self::TagNotExists(_) => StatusCode::NOT_FOUND,
+            Self::TagAlreadyExists(_) => StatusCode::BAD_REQUEST,
+            Self::ProjectNotExists(_) => StatusCode::NOT_FOUND,
+            Self::CommentNotExists(_) => StatusCode::NOT_FOUND,
+            Self::AttachmentNotExists(_) => StatusCode::NOT_FOUND,
+            Self::PayloadTooLarge(_) => StatusCode::PAYLOAD_TOO_LARGE,
+            Self::StatusNotExists(_) => StatusCode::NOT_FOUND,
+            Self::StatusAlreadyExists(_) => StatusCode::BAD_REQUEST,
+            Self::TransitionNotAllowed(_) => StatusCode::CONFLICT,
+            Self::TodoBlocked(_) => StatusCode::CONFLICT,
+            Self::TokenNotExists(_) => StatusCode::NOT_FOUND,
+            Self::InsufficientScope(_) => StatusCode::FORBIDDEN,
+        }
+    }
+}
+
+impl IntoResponse for AppError {
+    fn into_response(self) -> axum::response::Response {
+        let status = self.status_code();
+        // RFC 6750, tells the client a bearer token is expected, or one that
+        // grants more
+        let challenge = match self {
+            Self::AuthFailed(_) => Some(r#"Bearer realm="todolist""#),
+            Self::InsufficientScope(_) => {
+                Some(r#"Bearer realm="todolist", error="insufficient_scope""#)
+            }
+            _ => None,
         };
+        if let Some(challenge) = challenge {
+            return (
+                status,
+                [(header::WWW_AUTHENTICATE, challenge)],
+                format!("{}", self),
+            )
+                .into_response();
+        }
 
-        (status_code, format!("{}", self)).into_response()
+        (status, format!("{}", self)).into_response()
     }
 }
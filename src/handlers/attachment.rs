@@ -0,0 +1,250 @@
+use axum::{
+    body::Bytes,
+    extract::{multipart::MultipartError, Multipart, Path, State},
+    http::{header, StatusCode},
+    response::IntoResponse,
+    Json,
+};
+use rand_core::{OsRng, RngCore};
+use tracing::{info, warn};
+
+use crate::{
+    errors::AppError,
+    models::attachment::{self, Attachment, CreateAttachment},
+    storage::sniff_content_type,
+    AppState,
+};
+
+use super::{
+    extract::AuthUser,
+    request::{AttachmentResp, ListAttachmentsResp},
+    todolist::get_user_todo,
+};
+
+const MAX_FILENAME_LEN: usize = 255;
+
+/// Uploads the `file` field of a multipart form as an attachment of the todo.
+#[axum::debug_handler]
+pub async fn upload_attachment_handler(
+    AuthUser(user): AuthUser,
+    State(state): State<AppState>,
+    Path(id): Path<u64>,
+    mut multipart: Multipart,
+) -> Result<impl IntoResponse, AppError> {
+    info!("id: {}", id);
+
+    let todo = get_user_todo(id as i64, user.id, &state.pool).await?;
+
+    let max_size = state.storage_config.max_file_size;
+    let (filename, data) = loop {
+        let Some(mut field) = multipart.next_field().await.map_err(multipart_error)? else {
+            return Err(AppError::InvalidArgument(
+                "missing multipart field: file".to_string(),
+            ));
+        };
+        if field.name() != Some("file") {
+            continue;
+        }
+
+        let filename = sanitize_filename(field.file_name().unwrap_or_default());
+        let mut data = vec![];
+        while let Some(chunk) = field.chunk().await.map_err(multipart_error)? {
+            if (data.len() + chunk.len()) as u64 > max_size {
+                return Err(AppError::PayloadTooLarge(format!(
+                    "attachments must be at most {} bytes",
+                    max_size
+                )));
+            }
+            data.extend_from_slice(&chunk);
+        }
+        break (filename, Bytes::from(data));
+    };
+
+    let storage_key = new_storage_key(user.id);
+    let input = CreateAttachment {
+        todo_id: todo.id,
+        user_id: user.id,
+        filename,
+        content_type: sniff_content_type(&data).to_string(),
+        size: data.len() as i64,
+        storage_key,
+    };
+
+    state.storage.put(&input.storage_key, data).await?;
+    let attachment = match attachment::create_attachment(
+        &input,
+        state.storage_config.user_quota as i64,
+        &state.pool,
+    )
+    .await
+    {
+        Ok(attachment) => attachment,
+        Err(e) => {
+            state.storage.delete(&input.storage_key).await?;
+            return Err(e);
+        }
+    };
+
+    Ok((StatusCode::OK, Json(AttachmentResp { attachment })).into_response())
+}
+
+#[axum::debug_handler]
+pub async fn list_attachments_handler(
+    AuthUser(user): AuthUser,
+    State(state): State<AppState>,
+    Path(id): Path<u64>,
+) -> Result<impl IntoResponse, AppError> {
+    info!("id: {}", id);
+
+    let todo = get_user_todo(id as i64, user.id, &state.pool).await?;
+
+    let attachments = attachment::get_attachments_by_todo_id(todo.id, &state.pool).await?;
+
+    Ok((StatusCode::OK, Json(ListAttachmentsResp { attachments })).into_response())
+}
+
+#[axum::debug_handler]
+pub async fn download_attachment_handler(
+    AuthUser(user): AuthUser,
+    State(state): State<AppState>,
+    Path((id, aid)): Path<(u64, u64)>,
+) -> Result<impl IntoResponse, AppError> {
+    info!("id: {}, aid: {}", id, aid);
+
+    let todo = get_user_todo(id as i64, user.id, &state.pool).await?;
+    let attachment = get_todo_attachment(aid as i64, todo.id, &state).await?;
+
+    let data = state.storage.get(&attachment.storage_key).await?;
+
+    Ok((
+        StatusCode::OK,
+        [
+            (header::CONTENT_TYPE, attachment.content_type.clone()),
+            (
+                header::CONTENT_DISPOSITION,
+                content_disposition(&attachment.filename),
+            ),
+            (header::X_CONTENT_TYPE_OPTIONS, "nosniff".to_string()),
+        ],
+        data,
+    )
+        .into_response())
+}
+
+#[axum::debug_handler]
+pub async fn delete_attachment_handler(
+    AuthUser(user): AuthUser,
+    State(state): State<AppState>,
+    Path((id, aid)): Path<(u64, u64)>,
+) -> Result<impl IntoResponse, AppError> {
+    info!("id: {}, aid: {}", id, aid);
+
+    let todo = get_user_todo(id as i64, user.id, &state.pool).await?;
+    let attachment = get_todo_attachment(aid as i64, todo.id, &state).await?;
+
+    attachment::delete_attachment(attachment.id, &state.pool).await?;
+    if let Err(e) = state.storage.delete(&attachment.storage_key).await {
+        warn!("failed to delete {}: {}", attachment.storage_key, e);
+    }
+
+    Ok(StatusCode::NO_CONTENT.into_response())
+}
+
+async fn get_todo_attachment(
+    id: i64,
+    todo_id: i64,
+    state: &AppState,
+) -> Result<Attachment, AppError> {
+    attachment::get_attachment_by_id(id, &state.pool)
+        .await?
+        .filter(|a| a.todo_id == todo_id)
+        .ok_or_else(|| AppError::AttachmentNotExists(format!("attachment: {} not found", id)))
+}
+
+fn multipart_error(e: MultipartError) -> AppError {
+    if e.status() == StatusCode::PAYLOAD_TOO_LARGE {
+        AppError::PayloadTooLarge(e.body_text())
+    } else {
+        AppError::InvalidArgument(e.body_text())
+    }
+}
+
+/// A random key under the directory of the user.
+fn new_storage_key(user_id: i64) -> String {
+    let mut bytes = [0u8; 16];
+    OsRng.fill_bytes(&mut bytes);
+    let name: String = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+
+    format!("{}/{}", user_id, name)
+}
+
+/// The base name of an uploaded file without control characters, clients may
+/// send a full path.
+fn sanitize_filename(name: &str) -> String {
+    let base = name.rsplit(['/', '\\']).next().unwrap_or_default();
+    let name: String = base
+        .chars()
+        .filter(|c| !c.is_control())
+        .take(MAX_FILENAME_LEN)
+        .collect();
+    let name = name.trim();
+
+    if name.is_empty() || name == "." || name == ".." {
+        "attachment".to_string()
+    } else {
+        name.to_string()
+    }
+}
+
+/// `attachment` disposition with an ASCII fallback and the RFC 5987 encoded
+/// name.
+fn content_disposition(filename: &str) -> String {
+    let fallback: String = filename
+        .chars()
+        .map(|c| {
+            if c.is_ascii() && !c.is_ascii_control() && c != '"' && c != '\\' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    let encoded: String = filename
+        .bytes()
+        .map(|b| {
+            if b.is_ascii_alphanumeric() || b"!#$&+-.^_`|~".contains(&b) {
+                (b as char).to_string()
+            } else {
+                format!("%{:02X}", b)
+            }
+        })
+        .collect();
+
+    format!(
+        "attachment; filename=\"{}\"; filename*=UTF-8''{}",
+        fallback, encoded
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_attachment_filenames() {
+        assert_eq!(sanitize_filename("C:\\docs\\doc.txt"), "doc.txt");
+        assert_eq!(sanitize_filename("../../etc/passwd"), "passwd");
+        assert_eq!(sanitize_filename("a\r\nb.txt"), "ab.txt");
+        assert_eq!(sanitize_filename(".."), "attachment");
+        assert_eq!(sanitize_filename(""), "attachment");
+
+        assert_eq!(
+            content_disposition("my \"doc\".txt"),
+            "attachment; filename=\"my _doc_.txt\"; filename*=UTF-8''my%20%22doc%22.txt"
+        );
+        assert_eq!(
+            content_disposition("é.txt"),
+            "attachment; filename=\"_.txt\"; filename*=UTF-8''%C3%A9.txt"
+        );
+    }
+}
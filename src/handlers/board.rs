@@ -0,0 +1,69 @@
+use axum::{extract::State, http::StatusCode, response::IntoResponse, Json};
+use tracing::info;
+
+use crate::{
+    errors::AppError,
+    models::todolist::{self, GetTodosArgs},
+    AppState,
+};
+
+use super::{
+    extract::{AuthUser, ListParams},
+    request::{BoardColumnResp, BoardReq, BoardResp},
+    todolist::check_page,
+};
+
+#[axum::debug_handler]
+pub async fn get_board_handler(
+    AuthUser(user): AuthUser,
+    State(state): State<AppState>,
+    ListParams(req): ListParams<BoardReq>,
+) -> Result<impl IntoResponse, AppError> {
+    info!("req: {:?}", req);
+
+    check_page(0, req.limit)?;
+
+    let args = GetTodosArgs {
+        user_id: user.id,
+        project_id: req.project_id,
+        limit: req.limit,
+        tags: req.tags,
+        tag_match: req.tag_match,
+        ..Default::default()
+    };
+    let board = todolist::get_board(&args, &state.pool).await?;
+
+    // relations of every column in one go
+    let mut todos = vec![];
+    let mut columns = vec![];
+    for column in board {
+        let next_cursor = match column.page.next {
+            Some(cursor) => Some(state.auth.sign_cursor(cursor)?),
+            None => None,
+        };
+        columns.push((
+            column.status,
+            column.count,
+            column.page.todos.len(),
+            next_cursor,
+        ));
+        todos.extend(column.page.todos);
+    }
+    todolist::load_relations(&mut todos, &state.pool).await?;
+
+    let mut todos = todos.into_iter();
+    let columns = columns
+        .into_iter()
+        .map(|(status, count, len, next_cursor)| BoardColumnResp {
+            status: status.id,
+            name: status.name,
+            color: status.color,
+            is_done: status.is_done,
+            count,
+            todos: todos.by_ref().take(len).collect(),
+            next_cursor,
+        })
+        .collect();
+
+    Ok((StatusCode::OK, Json(BoardResp { columns })).into_response())
+}
@@ -0,0 +1,134 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use tracing::info;
+
+use crate::{
+    errors::AppError,
+    models::comment::{self, Comment, CreateComment},
+    AppState,
+};
+
+use super::{
+    extract::{AuthUser, ListParams},
+    request::{CommentResp, CreateCommentReq, ListCommentsReq, ListCommentsResp, UpdateCommentReq},
+    todolist::{check_page, get_user_todo},
+};
+
+const MAX_COMMENT_LEN: usize = 4096;
+
+#[axum::debug_handler]
+pub async fn create_comment_handler(
+    AuthUser(user): AuthUser,
+    State(state): State<AppState>,
+    Path(id): Path<u64>,
+    Json(req): Json<CreateCommentReq>,
+) -> Result<impl IntoResponse, AppError> {
+    info!("id: {}, req: {:?}", id, req);
+
+    let todo = get_user_todo(id as i64, user.id, &state.pool).await?;
+    check_comment_body(&req.body)?;
+
+    let comment = comment::create_comment(
+        &CreateComment {
+            todo_id: todo.id,
+            user_id: user.id,
+            body: req.body,
+        },
+        &state.pool,
+    )
+    .await?;
+
+    Ok((StatusCode::OK, Json(CommentResp { comment })).into_response())
+}
+
+#[axum::debug_handler]
+pub async fn list_comments_handler(
+    AuthUser(user): AuthUser,
+    State(state): State<AppState>,
+    Path(id): Path<u64>,
+    ListParams(req): ListParams<ListCommentsReq>,
+) -> Result<impl IntoResponse, AppError> {
+    info!("id: {}, req: {:?}", id, req);
+
+    check_page(req.offset, req.limit)?;
+    let todo = get_user_todo(id as i64, user.id, &state.pool).await?;
+
+    let mut comments =
+        comment::get_comments_by_todo_id(todo.id, req.offset, req.limit + 1, &state.pool).await?;
+
+    let has_more = comments.len() > req.limit as usize;
+    if has_more {
+        comments.pop();
+    }
+
+    Ok((
+        StatusCode::OK,
+        Json(ListCommentsResp { comments, has_more }),
+    )
+        .into_response())
+}
+
+#[axum::debug_handler]
+pub async fn update_comment_handler(
+    AuthUser(user): AuthUser,
+    State(state): State<AppState>,
+    Path((id, cid)): Path<(u64, u64)>,
+    Json(req): Json<UpdateCommentReq>,
+) -> Result<impl IntoResponse, AppError> {
+    info!("id: {}, cid: {}, req: {:?}", id, cid, req);
+
+    let todo = get_user_todo(id as i64, user.id, &state.pool).await?;
+    get_user_comment(cid as i64, todo.id, user.id, &state).await?;
+    check_comment_body(&req.body)?;
+
+    let comment = comment::update_comment(cid as i64, &req.body, &state.pool).await?;
+
+    Ok((StatusCode::OK, Json(CommentResp { comment })).into_response())
+}
+
+#[axum::debug_handler]
+pub async fn delete_comment_handler(
+    AuthUser(user): AuthUser,
+    State(state): State<AppState>,
+    Path((id, cid)): Path<(u64, u64)>,
+) -> Result<impl IntoResponse, AppError> {
+    info!("id: {}, cid: {}", id, cid);
+
+    let todo = get_user_todo(id as i64, user.id, &state.pool).await?;
+    get_user_comment(cid as i64, todo.id, user.id, &state).await?;
+
+    comment::delete_comment(cid as i64, &state.pool).await?;
+
+    Ok(StatusCode::NO_CONTENT.into_response())
+}
+
+/// The comment `id` of the todo, only its author may change it and other
+/// users' comments are answered as missing.
+async fn get_user_comment(
+    id: i64,
+    todo_id: i64,
+    user_id: i64,
+    state: &AppState,
+) -> Result<Comment, AppError> {
+    let comment = comment::get_comment_by_id(id, user_id, &state.pool)
+        .await?
+        .filter(|c| c.todo_id == todo_id)
+        .ok_or_else(|| AppError::CommentNotExists(format!("comment: {} not found", id)))?;
+
+    Ok(comment)
+}
+
+fn check_comment_body(body: &str) -> Result<(), AppError> {
+    if body.trim().is_empty() || body.chars().count() > MAX_COMMENT_LEN {
+        return Err(AppError::InvalidArgument(format!(
+            "comment must be 1 to {} characters",
+            MAX_COMMENT_LEN
+        )));
+    }
+
+    Ok(())
+}
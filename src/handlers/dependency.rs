@@ -0,0 +1,80 @@
+use std::slice;
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use tracing::info;
+
+use crate::{
+    errors::AppError,
+    models::{dependency, todolist},
+    AppState,
+};
+
+use super::{
+    extract::AuthUser,
+    request::{CreateTodoResp, DependenciesResp},
+    todolist::get_user_todo,
+};
+
+#[axum::debug_handler]
+pub async fn list_dependencies_handler(
+    AuthUser(user): AuthUser,
+    State(state): State<AppState>,
+    Path(id): Path<u64>,
+) -> Result<impl IntoResponse, AppError> {
+    info!("id: {}", id);
+
+    let todo = get_user_todo(id as i64, user.id, &state.pool).await?;
+
+    let mut blockers = dependency::get_blockers(todo.id, &state.pool).await?;
+    let mut dependents = dependency::get_dependents(todo.id, &state.pool).await?;
+    todolist::load_relations(&mut blockers, &state.pool).await?;
+    todolist::load_relations(&mut dependents, &state.pool).await?;
+
+    Ok((
+        StatusCode::OK,
+        Json(DependenciesResp {
+            blockers,
+            dependents,
+        }),
+    )
+        .into_response())
+}
+
+/// Makes the todo wait for `blocker_id`.
+#[axum::debug_handler]
+pub async fn add_dependency_handler(
+    AuthUser(user): AuthUser,
+    State(state): State<AppState>,
+    Path((id, blocker_id)): Path<(u64, u64)>,
+) -> Result<impl IntoResponse, AppError> {
+    info!("id: {}, blocker_id: {}", id, blocker_id);
+
+    let mut todo = get_user_todo(id as i64, user.id, &state.pool).await?;
+    let blocker = get_user_todo(blocker_id as i64, user.id, &state.pool).await?;
+
+    dependency::add_dependency(todo.id, blocker.id, &state.pool).await?;
+    todolist::load_relations(slice::from_mut(&mut todo), &state.pool).await?;
+
+    Ok((StatusCode::OK, Json(CreateTodoResp { todo })).into_response())
+}
+
+#[axum::debug_handler]
+pub async fn remove_dependency_handler(
+    AuthUser(user): AuthUser,
+    State(state): State<AppState>,
+    Path((id, blocker_id)): Path<(u64, u64)>,
+) -> Result<impl IntoResponse, AppError> {
+    info!("id: {}, blocker_id: {}", id, blocker_id);
+
+    let mut todo = get_user_todo(id as i64, user.id, &state.pool).await?;
+
+    dependency::remove_dependency(todo.id, blocker_id as i64, &state.pool).await?;
+    todolist::load_relations(slice::from_mut(&mut todo), &state.pool).await?;
+
+    Ok((StatusCode::OK, Json(CreateTodoResp { todo })).into_response())
+}
@@ -0,0 +1,257 @@
+use axum::{
+    async_trait,
+    body::Bytes,
+    extract::{FromRequest, FromRequestParts, Query, Request, State},
+    http::{request::Parts, Method},
+    middleware::Next,
+    response::Response,
+    Json,
+};
+use axum_extra::{
+    headers::{authorization::Bearer, Authorization},
+    TypedHeader,
+};
+use jwt_simple::claims::JWTClaims;
+use serde::de::DeserializeOwned;
+use tracing::warn;
+
+use crate::{
+    auth::{self, AccessClaims},
+    errors::AppError,
+    models::{
+        personal_token::{self, Scope},
+        user::{self, User},
+    },
+    AppState,
+};
+
+/// The user a request is made for, signed in with a JWT or using one of their
+/// personal access tokens.
+///
+/// Routes behind `require_auth` get the user it already verified, elsewhere
+/// the bearer token is verified here.
+#[derive(Debug, Clone)]
+pub struct AuthUser(pub User);
+
+#[async_trait]
+impl FromRequestParts<AppState> for AuthUser {
+    type Rejection = AppError;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &AppState,
+    ) -> Result<Self, Self::Rejection> {
+        if let Some(user) = parts.extensions.get::<AuthUser>() {
+            return Ok(user.clone());
+        }
+
+        let TypedHeader(Authorization(bearer)) =
+            TypedHeader::<Authorization<Bearer>>::from_request_parts(parts, state)
+                .await
+                .map_err(|_| AppError::AuthFailed("missing bearer token".to_string()))?;
+        let token = bearer.token();
+        if !auth::is_personal_token(token) {
+            let claims = state.auth.verify_claims(token)?;
+            let user = claims.custom.user.clone();
+            parts.extensions.insert(AuthClaims(claims));
+            return Ok(Self(user));
+        }
+
+        let Some(scope) = required_scope(&parts.method, parts.uri.path()) else {
+            return Err(AppError::InsufficientScope(
+                "personal access tokens only give access to todos".to_string(),
+            ));
+        };
+        let pat = personal_token::use_personal_token(&auth::hash_token(token), &state.pool).await?;
+        if !pat.allows(scope) {
+            return Err(AppError::InsufficientScope(format!(
+                "token lacks scope: {}",
+                scope
+            )));
+        }
+        let user = user::get_user_by_id(pat.user_id, &state.pool)
+            .await?
+            .ok_or_else(|| AppError::AuthFailed(format!("user: {} not found", pat.user_id)))?;
+
+        Ok(Self(user))
+    }
+}
+
+/// Claims of the access token a request is signed in with, for the handlers
+/// acting on the token itself. Personal access tokens have none.
+#[derive(Debug, Clone)]
+pub struct AuthClaims(pub JWTClaims<AccessClaims>);
+
+#[async_trait]
+impl FromRequestParts<AppState> for AuthClaims {
+    type Rejection = AppError;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &AppState,
+    ) -> Result<Self, Self::Rejection> {
+        if parts.extensions.get::<AuthClaims>().is_none() {
+            AuthUser::from_request_parts(parts, state).await?;
+        }
+
+        parts
+            .extensions
+            .get::<AuthClaims>()
+            .cloned()
+            .ok_or_else(|| AppError::InsufficientScope("a signed in session is needed".to_string()))
+    }
+}
+
+/// Scope a personal access token needs for the request, `None` for the routes
+/// that take a signed in user.
+fn required_scope(method: &Method, path: &str) -> Option<Scope> {
+    let todos = path == "/todos" || path.starts_with("/todos/") || path == "/board";
+    if !todos {
+        return None;
+    }
+
+    match *method {
+        Method::GET | Method::HEAD => Some(Scope::TodosRead),
+        _ => Some(Scope::TodosWrite),
+    }
+}
+
+/// Layer for the routes that need a signed in user, requests without a valid
+/// bearer token are turned away before reaching the handler.
+pub async fn require_auth(
+    State(state): State<AppState>,
+    req: Request,
+    next: Next,
+) -> Result<Response, AppError> {
+    let (mut parts, body) = req.into_parts();
+    let user = AuthUser::from_request_parts(&mut parts, &state).await?;
+    parts.extensions.insert(user);
+
+    Ok(next.run(Request::from_parts(parts, body)).await)
+}
+
+/// Parameters of a list endpoint, read from the query string.
+///
+/// Clients used to send them as a JSON body on the GET request, a non empty
+/// body is still accepted in place of the query string until they have all
+/// moved over.
+#[derive(Debug)]
+pub struct ListParams<T>(pub T);
+
+#[async_trait]
+impl<S, T> FromRequest<S> for ListParams<T>
+where
+    S: Send + Sync,
+    T: DeserializeOwned,
+{
+    type Rejection = AppError;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let uri = req.uri().clone();
+        let body = Bytes::from_request(req, state)
+            .await
+            .map_err(|e| AppError::InvalidArgument(e.body_text()))?;
+
+        if !body.is_empty() {
+            warn!("list parameters sent as a JSON body to {}", uri.path());
+            let Json(params) =
+                Json::from_bytes(&body).map_err(|e| AppError::InvalidArgument(e.body_text()))?;
+            return Ok(Self(params));
+        }
+
+        let Query(params) =
+            Query::try_from_uri(&uri).map_err(|e| AppError::InvalidArgument(e.body_text()))?;
+
+        Ok(Self(params))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{
+        body::Body,
+        http::{header, StatusCode},
+        response::IntoResponse,
+    };
+
+    use crate::{handlers::request::ListTodosReq, models::status::StatusKey};
+
+    use super::*;
+    use anyhow::Result;
+
+    #[tokio::test]
+    async fn test_list_params_from_query() -> Result<()> {
+        let req = Request::get("/todos?offset=10&status=Pending,2&tags=1,2&overdue=true")
+            .body(Body::empty())?;
+        let ListParams(params) = ListParams::<ListTodosReq>::from_request(req, &()).await?;
+        assert_eq!(params.offset, 10);
+        assert_eq!(params.limit, 20);
+        assert_eq!(
+            params.status,
+            vec![StatusKey::Name("Pending".to_string()), StatusKey::Id(2)]
+        );
+        assert_eq!(params.tags, vec![1, 2]);
+        assert!(params.overdue);
+
+        let req = Request::get("/todos?limit=ten").body(Body::empty())?;
+        let res = ListParams::<ListTodosReq>::from_request(req, &()).await;
+        assert!(matches!(res, Err(AppError::InvalidArgument(_))));
+
+        let req = Request::get("/todos?tags=work").body(Body::empty())?;
+        let res = ListParams::<ListTodosReq>::from_request(req, &()).await;
+        assert!(matches!(res, Err(AppError::InvalidArgument(_))));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_list_params_from_body() -> Result<()> {
+        let req = Request::get("/todos")
+            .header("content-type", "application/json")
+            .body(Body::from(
+                r#"{"offset":0,"limit":5,"status":["Done",1],"tags":[3]}"#,
+            ))?;
+        let ListParams(params) = ListParams::<ListTodosReq>::from_request(req, &()).await?;
+        assert_eq!(params.limit, 5);
+        assert_eq!(
+            params.status,
+            vec![StatusKey::Name("Done".to_string()), StatusKey::Id(1)]
+        );
+        assert_eq!(params.tags, vec![3]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_required_scope() {
+        assert_eq!(
+            required_scope(&Method::GET, "/todos/search"),
+            Some(Scope::TodosRead)
+        );
+        assert_eq!(
+            required_scope(&Method::GET, "/board"),
+            Some(Scope::TodosRead)
+        );
+        assert_eq!(
+            required_scope(&Method::POST, "/todos"),
+            Some(Scope::TodosWrite)
+        );
+        assert_eq!(
+            required_scope(&Method::DELETE, "/todos/1/tags/2"),
+            Some(Scope::TodosWrite)
+        );
+        assert_eq!(required_scope(&Method::GET, "/todosx"), None);
+        assert_eq!(required_scope(&Method::GET, "/tokens"), None);
+        assert_eq!(required_scope(&Method::POST, "/signout"), None);
+    }
+
+    #[test]
+    fn test_auth_rejection() {
+        let res = AppError::AuthFailed("missing bearer token".to_string()).into_response();
+        assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(
+            res.headers()[header::WWW_AUTHENTICATE],
+            r#"Bearer realm="todolist""#
+        );
+    }
+}
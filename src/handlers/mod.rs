@@ -1,4 +1,13 @@
+pub mod attachment;
+pub mod board;
+pub mod comment;
+pub mod dependency;
+pub mod extract;
+pub mod personal_token;
+pub mod project;
 pub mod request;
+pub mod status;
+pub mod tag;
 pub mod todolist;
 pub mod user;
 pub mod utils;
@@ -0,0 +1,111 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use chrono::Utc;
+use tracing::info;
+
+use crate::{
+    auth,
+    errors::AppError,
+    models::personal_token::{self, CreatePersonalToken},
+    AppState,
+};
+
+use super::{
+    extract::AuthUser,
+    request::{CreatePersonalTokenReq, CreatePersonalTokenResp, ListPersonalTokensResp},
+};
+
+const MAX_TOKEN_NAME_LEN: usize = 64;
+/// Characters of a token kept to tell it apart in listings.
+const TOKEN_HINT_LEN: usize = 12;
+
+#[axum::debug_handler]
+pub async fn create_personal_token_handler(
+    AuthUser(user): AuthUser,
+    State(state): State<AppState>,
+    Json(req): Json<CreatePersonalTokenReq>,
+) -> Result<impl IntoResponse, AppError> {
+    info!("{:?}", req);
+
+    check_token_name(&req.name)?;
+    if req.scopes.is_empty() {
+        return Err(AppError::InvalidArgument(
+            "a token needs at least one scope".to_string(),
+        ));
+    }
+    if req
+        .expires_at
+        .is_some_and(|expires_at| expires_at <= Utc::now())
+    {
+        return Err(AppError::InvalidArgument(
+            "expires_at must be in the future".to_string(),
+        ));
+    }
+
+    let token = auth::gen_personal_token();
+    let personal_token = personal_token::create_personal_token(
+        &CreatePersonalToken {
+            user_id: user.id,
+            name: req.name.trim().to_string(),
+            token_hash: auth::hash_token(&token),
+            token_hint: token[..TOKEN_HINT_LEN].to_string(),
+            scopes: req.scopes,
+            expires_at: req.expires_at,
+        },
+        &state.pool,
+    )
+    .await?;
+    info!(
+        "user: {} created personal token: {}",
+        user.id, personal_token.id
+    );
+
+    Ok((
+        StatusCode::OK,
+        Json(CreatePersonalTokenResp {
+            token,
+            personal_token,
+        }),
+    )
+        .into_response())
+}
+
+#[axum::debug_handler]
+pub async fn list_personal_tokens_handler(
+    AuthUser(user): AuthUser,
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, AppError> {
+    let tokens = personal_token::get_personal_tokens_by_user_id(user.id, &state.pool).await?;
+
+    Ok((StatusCode::OK, Json(ListPersonalTokensResp { tokens })).into_response())
+}
+
+#[axum::debug_handler]
+pub async fn revoke_personal_token_handler(
+    AuthUser(user): AuthUser,
+    State(state): State<AppState>,
+    Path(id): Path<u64>,
+) -> Result<impl IntoResponse, AppError> {
+    info!("id: {}", id);
+
+    personal_token::revoke_personal_token(id as i64, user.id, &state.pool).await?;
+    info!("user: {} revoked personal token: {}", user.id, id);
+
+    Ok(StatusCode::NO_CONTENT.into_response())
+}
+
+fn check_token_name(name: &str) -> Result<(), AppError> {
+    let name = name.trim();
+    if name.is_empty() || name.chars().count() > MAX_TOKEN_NAME_LEN {
+        return Err(AppError::InvalidArgument(format!(
+            "token name must be 1 to {} characters",
+            MAX_TOKEN_NAME_LEN
+        )));
+    }
+
+    Ok(())
+}
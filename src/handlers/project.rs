@@ -0,0 +1,138 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use sqlx::PgExecutor;
+use tracing::info;
+
+use crate::{
+    errors::AppError,
+    models::project::{self, CreateProject, Project, UpdateProject},
+    AppState,
+};
+
+use super::{
+    extract::AuthUser,
+    request::{CreateProjectReq, ListProjectsResp, ProjectResp, UpdateProjectReq},
+};
+
+const MAX_PROJECT_NAME_LEN: usize = 128;
+
+#[axum::debug_handler]
+pub async fn create_project_handler(
+    AuthUser(user): AuthUser,
+    State(state): State<AppState>,
+    Json(req): Json<CreateProjectReq>,
+) -> Result<impl IntoResponse, AppError> {
+    info!("{:?}", req);
+
+    check_project_name(&req.name)?;
+
+    let project = project::create_project(
+        &CreateProject {
+            user_id: user.id,
+            name: req.name.trim().to_string(),
+            description: req.description,
+            color: req.color,
+            position: req.position,
+        },
+        &state.pool,
+    )
+    .await?;
+
+    Ok((StatusCode::OK, Json(ProjectResp { project })).into_response())
+}
+
+#[axum::debug_handler]
+pub async fn list_projects_handler(
+    AuthUser(user): AuthUser,
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, AppError> {
+    let projects = project::get_projects_by_user_id(user.id, &state.pool).await?;
+
+    Ok((StatusCode::OK, Json(ListProjectsResp { projects })).into_response())
+}
+
+#[axum::debug_handler]
+pub async fn get_project_handler(
+    AuthUser(user): AuthUser,
+    State(state): State<AppState>,
+    Path(id): Path<u64>,
+) -> Result<impl IntoResponse, AppError> {
+    info!("id: {}", id);
+
+    let project = get_user_project(id as i64, user.id, &state.pool).await?;
+
+    Ok((StatusCode::OK, Json(ProjectResp { project })).into_response())
+}
+
+#[axum::debug_handler]
+pub async fn update_project_handler(
+    AuthUser(user): AuthUser,
+    State(state): State<AppState>,
+    Path(id): Path<u64>,
+    Json(req): Json<UpdateProjectReq>,
+) -> Result<impl IntoResponse, AppError> {
+    info!("req: {:?}", req);
+
+    let project = get_user_project(id as i64, user.id, &state.pool).await?;
+    if let Some(name) = &req.name {
+        check_project_name(name)?;
+    }
+    if project.is_inbox && req.archived == Some(true) {
+        return Err(AppError::InvalidArgument(
+            "the inbox can not be archived".to_string(),
+        ));
+    }
+
+    let project =
+        project::update_project(id as i64, user.id, &UpdateProject::from(req), &state.pool).await?;
+
+    Ok((StatusCode::OK, Json(ProjectResp { project })).into_response())
+}
+
+#[axum::debug_handler]
+pub async fn delete_project_handler(
+    AuthUser(user): AuthUser,
+    State(state): State<AppState>,
+    Path(id): Path<u64>,
+) -> Result<impl IntoResponse, AppError> {
+    info!("id: {}", id);
+
+    let project = get_user_project(id as i64, user.id, &state.pool).await?;
+    if project.is_inbox {
+        return Err(AppError::InvalidArgument(
+            "the inbox can not be deleted".to_string(),
+        ));
+    }
+
+    project::delete_project(id as i64, &state.pool).await?;
+
+    Ok(StatusCode::NO_CONTENT.into_response())
+}
+
+pub(crate) async fn get_user_project<'c>(
+    id: i64,
+    user_id: i64,
+    executor: impl PgExecutor<'c>,
+) -> Result<Project, AppError> {
+    let project = project::get_project_by_id(id, user_id, executor)
+        .await?
+        .ok_or_else(|| AppError::ProjectNotExists(format!("project: {} not found", id)))?;
+
+    Ok(project)
+}
+
+fn check_project_name(name: &str) -> Result<(), AppError> {
+    let name = name.trim();
+    if name.is_empty() || name.chars().count() > MAX_PROJECT_NAME_LEN {
+        return Err(AppError::InvalidArgument(format!(
+            "project name must be 1 to {} characters",
+            MAX_PROJECT_NAME_LEN
+        )));
+    }
+
+    Ok(())
+}
@@ -1,8 +1,25 @@
-use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, fmt, str::FromStr};
 
-use crate::models::{
-    todolist::{Todo, TodoStatus},
-    user::User,
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Deserializer, Serialize};
+
+use crate::{
+    errors::AppError,
+    models::{
+        attachment::Attachment,
+        comment::Comment,
+        event::TodoEvent,
+        personal_token::{PersonalToken, Scope},
+        project::{Project, UpdateProject},
+        recurrence::RecurrenceRule,
+        search::SearchMatch,
+        status::{Status, StatusKey},
+        tag::Tag,
+        todolist::{
+            DueWindow, SortOrder, StatusCount, TagMatch, Todo, TodoPriority, TodoSort, UpdateTodo,
+        },
+        user::User,
+    },
 };
 
 #[derive(Debug, Deserialize)]
@@ -16,6 +33,9 @@ pub struct SignupReq {
 pub struct SignupResp {
     pub user: User,
     pub token: String,
+    pub refresh_token: String,
+    /// Seconds until `token` expires.
+    pub expires_in: u64,
 }
 
 #[derive(Debug, Deserialize)]
@@ -27,13 +47,60 @@ pub struct LoginReq {
 #[derive(Debug, Serialize)]
 pub struct LoginResp {
     pub token: String,
+    /// Single use, `POST /token/refresh` trades it for a new pair.
+    pub refresh_token: String,
+    /// Seconds until `token` expires.
+    pub expires_in: u64,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RefreshTokenReq {
+    pub refresh_token: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreatePersonalTokenReq {
+    pub name: String,
+    pub scopes: Vec<Scope>,
+    /// The token never expires without one.
+    #[serde(default)]
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CreatePersonalTokenResp {
+    /// Only shown here, it can't be retrieved later.
+    pub token: String,
+    pub personal_token: PersonalToken,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ListPersonalTokensResp {
+    pub tokens: Vec<PersonalToken>,
 }
 
 #[derive(Debug, Deserialize)]
 pub struct CreateTodoReq {
     pub title: String,
     pub description: String,
-    pub status: TodoStatus,
+    /// Id or name of one of the user's statuses.
+    pub status: StatusKey,
+    #[serde(default)]
+    pub priority: TodoPriority,
+    /// Defaults to the parent's project for subtasks and the Inbox otherwise.
+    #[serde(default)]
+    pub project_id: Option<i64>,
+    #[serde(default)]
+    pub parent_id: Option<i64>,
+    #[serde(default)]
+    pub auto_complete: bool,
+    #[serde(default)]
+    pub start_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub due_at: Option<DateTime<Utc>>,
+    /// RRULE subset, e.g. `FREQ=WEEKLY;BYDAY=MO`, needs `due_at` or `start_at`.
+    #[serde(default)]
+    pub recurrence: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
@@ -41,20 +108,564 @@ pub struct CreateTodoResp {
     pub todo: Todo,
 }
 
+/// Page size of list endpoints when `limit` is not given.
+pub const DEFAULT_PAGE_SIZE: i64 = 20;
+pub const MAX_PAGE_SIZE: i64 = 100;
+
+fn default_limit() -> i64 {
+    DEFAULT_PAGE_SIZE
+}
+
+/// Parameters of `GET /todos`, read from the query string. List values are
+/// comma separated there: `status=Pending,InProgress&tags=1,2`.
 #[derive(Debug, Deserialize)]
 pub struct ListTodosReq {
+    #[serde(default)]
     pub offset: i64,
+    #[serde(default = "default_limit")]
+    pub limit: i64,
+    /// Any status when absent.
+    #[serde(default, deserialize_with = "deserialize_list")]
+    pub status: Vec<StatusKey>,
+    #[serde(default, deserialize_with = "deserialize_list")]
+    pub exclude_status: Vec<StatusKey>,
+    #[serde(default)]
+    pub project_id: Option<i64>,
+    #[serde(default)]
+    pub due_before: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub due_after: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub overdue: bool,
+    #[serde(default)]
+    pub due: Option<DueWindow>,
+    #[serde(default)]
+    pub timezone: Option<String>,
+    #[serde(default)]
+    pub sort: Option<TodoSort>,
+    #[serde(default)]
+    pub order: Option<SortOrder>,
+    #[serde(default, deserialize_with = "deserialize_list")]
+    pub tags: Vec<i64>,
+    #[serde(default)]
+    pub tag_match: TagMatch,
+    /// `next_cursor` or `prev_cursor` of a previous page, replaces `offset`.
+    #[serde(default)]
+    pub cursor: Option<String>,
+}
+
+/// Parameters of `GET /todos/search`, see `SearchQuery` for the syntax of `q`.
+#[derive(Debug, Deserialize)]
+pub struct SearchTodosReq {
+    pub q: String,
+    #[serde(default)]
+    pub offset: i64,
+    #[serde(default = "default_limit")]
+    pub limit: i64,
+    #[serde(default, deserialize_with = "deserialize_list")]
+    pub status: Vec<StatusKey>,
+    #[serde(default, deserialize_with = "deserialize_list")]
+    pub exclude_status: Vec<StatusKey>,
+    #[serde(default)]
+    pub project_id: Option<i64>,
+    #[serde(default, deserialize_with = "deserialize_list")]
+    pub tags: Vec<i64>,
+    #[serde(default)]
+    pub tag_match: TagMatch,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SearchResult {
+    #[serde(flatten)]
+    pub todo: Todo,
+    #[serde(rename = "match")]
+    pub matched: SearchMatch,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SearchTodosResp {
+    pub results: Vec<SearchResult>,
+    pub has_more: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ListTrashReq {
+    #[serde(default)]
+    pub offset: i64,
+    #[serde(default = "default_limit")]
     pub limit: i64,
-    pub status: TodoStatus,
 }
 
 #[derive(Debug, Deserialize)]
 pub struct UpdateTodosReq {
-    pub status: TodoStatus,
+    pub status: StatusKey,
+    /// Refuse to start or finish the todo while it waits for others.
+    #[serde(default)]
+    pub refuse_blocked: bool,
+}
+
+/// Body of `POST /todos/:id/move`, the todos the moved one goes between.
+/// With a `status` the todo also changes column on the board, it goes to the
+/// end of the column when neither `after_id` nor `before_id` is given.
+#[derive(Debug, Deserialize)]
+pub struct MoveTodoReq {
+    #[serde(default)]
+    pub status: Option<StatusKey>,
+    pub after_id: Option<i64>,
+    pub before_id: Option<i64>,
+}
+
+/// Parameters of `GET /board`, `limit` applies to each column.
+#[derive(Debug, Deserialize)]
+pub struct BoardReq {
+    #[serde(default = "default_limit")]
+    pub limit: i64,
+    #[serde(default)]
+    pub project_id: Option<i64>,
+    #[serde(default, deserialize_with = "deserialize_list")]
+    pub tags: Vec<i64>,
+    #[serde(default)]
+    pub tag_match: TagMatch,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BoardResp {
+    pub columns: Vec<BoardColumnResp>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BoardColumnResp {
+    pub status: i16,
+    pub name: String,
+    pub color: String,
+    pub is_done: bool,
+    pub count: i64,
+    pub todos: Vec<Todo>,
+    /// Cursor of `GET /todos?status=<name>&sort=manual` (with the same
+    /// filters) for the rest of the column.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
+}
+
+/// Body of `POST /todos/bulk`.
+#[derive(Debug, Deserialize)]
+pub struct BulkTodosReq {
+    #[serde(default)]
+    pub mode: BulkMode,
+    pub operations: Vec<BulkOperation>,
+}
+
+#[derive(Debug, Default, Clone, Copy, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum BulkMode {
+    /// The first failing operation rolls back all of them.
+    #[default]
+    AllOrNothing,
+    /// Failing operations are skipped, the others are kept.
+    BestEffort,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum BulkOperation {
+    Status {
+        id: i64,
+        status: StatusKey,
+    },
+    /// Same fields as `PATCH /todos/:id`.
+    Set {
+        id: i64,
+        #[serde(flatten)]
+        fields: PatchTodoReq,
+    },
+    Move {
+        id: i64,
+        project_id: Option<i64>,
+    },
+    AddTag {
+        id: i64,
+        tag_id: i64,
+    },
+    Delete {
+        id: i64,
+    },
+}
+
+impl BulkOperation {
+    pub fn todo_id(&self) -> i64 {
+        match self {
+            Self::Status { id, .. }
+            | Self::Set { id, .. }
+            | Self::Move { id, .. }
+            | Self::AddTag { id, .. }
+            | Self::Delete { id } => *id,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct BulkTodosResp {
+    /// Whether the changes were saved, false once an all or nothing batch fails.
+    pub committed: bool,
+    /// One per operation in request order, an all or nothing batch stops at
+    /// the first failure.
+    pub results: Vec<BulkResult>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BulkResult {
+    pub id: i64,
+    /// The HTTP status the operation would have on its own endpoint.
+    pub status: u16,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub todo: Option<Todo>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Body of `PATCH /todos/:id`, an absent field is left untouched while an
+/// explicit `null` is deserialized as `Some(None)`.
+#[derive(Debug, Default, Deserialize)]
+pub struct PatchTodoReq {
+    #[serde(default, deserialize_with = "deserialize_some")]
+    pub title: Option<Option<String>>,
+    #[serde(default, deserialize_with = "deserialize_some")]
+    pub description: Option<Option<String>>,
+    #[serde(default, deserialize_with = "deserialize_some")]
+    pub status: Option<Option<StatusKey>>,
+    #[serde(default, deserialize_with = "deserialize_some")]
+    pub priority: Option<Option<TodoPriority>>,
+    #[serde(default, deserialize_with = "deserialize_some")]
+    pub start_at: Option<Option<DateTime<Utc>>>,
+    #[serde(default, deserialize_with = "deserialize_some")]
+    pub due_at: Option<Option<DateTime<Utc>>>,
+    #[serde(default, deserialize_with = "deserialize_some")]
+    pub recurrence: Option<Option<String>>,
+    #[serde(default, deserialize_with = "deserialize_some")]
+    pub project_id: Option<Option<i64>>,
+    #[serde(default, deserialize_with = "deserialize_some")]
+    pub parent_id: Option<Option<i64>>,
+    #[serde(default)]
+    pub auto_complete: Option<bool>,
+    /// Refuse to start or finish the todo while it waits for others.
+    #[serde(default)]
+    pub refuse_blocked: bool,
+}
+
+impl PatchTodoReq {
+    /// The update to apply, `statuses` are those of the user.
+    pub fn into_update(self, statuses: &[Status]) -> Result<UpdateTodo, AppError> {
+        let title = match self.title {
+            Some(None) => {
+                return Err(AppError::InvalidArgument(
+                    "title can not be null".to_string(),
+                ))
+            }
+            Some(Some(title)) if title.trim().is_empty() => {
+                return Err(AppError::InvalidArgument(
+                    "title can not be empty".to_string(),
+                ))
+            }
+            title => title.flatten(),
+        };
+
+        let status = match self.status {
+            Some(None) => {
+                return Err(AppError::InvalidArgument(
+                    "status can not be null".to_string(),
+                ))
+            }
+            Some(Some(status)) => Some(status.resolve(statuses)?),
+            None => None,
+        };
+
+        let priority = match self.priority {
+            Some(None) => {
+                return Err(AppError::InvalidArgument(
+                    "priority can not be null".to_string(),
+                ))
+            }
+            priority => priority.flatten().map(|p| p.get_priority_id()),
+        };
+
+        let recurrence = match self.recurrence {
+            Some(Some(rule)) => Some(Some(rule.parse::<RecurrenceRule>()?.to_string())),
+            recurrence => recurrence,
+        };
+
+        Ok(UpdateTodo {
+            title,
+            description: self.description.map(Option::unwrap_or_default),
+            status,
+            priority,
+            start_at: self.start_at,
+            due_at: self.due_at,
+            recurrence,
+            project_id: self.project_id,
+            parent_id: self.parent_id,
+            auto_complete: self.auto_complete,
+            refuse_blocked: self.refuse_blocked,
+        })
+    }
+}
+
+/// Deserializes a list given either as an array or as a comma separated
+/// string, the only form a query string can carry.
+fn deserialize_list<'de, T, D>(deserializer: D) -> Result<Vec<T>, D::Error>
+where
+    T: Deserialize<'de> + FromStr,
+    T::Err: fmt::Display,
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum OneOrMany<T> {
+        One(String),
+        Many(Vec<T>),
+    }
+
+    match OneOrMany::deserialize(deserializer)? {
+        OneOrMany::One(s) => s
+            .split(',')
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(|s| s.parse().map_err(serde::de::Error::custom))
+            .collect(),
+        OneOrMany::Many(items) => Ok(items),
+    }
+}
+
+fn deserialize_some<'de, T, D>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    T: Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    T::deserialize(deserializer).map(Some)
 }
 
 #[derive(Debug, Serialize)]
 pub struct ListTodosResp {
     pub todos: Vec<Todo>,
     pub has_more: bool,
+    /// Todos per status for the same filters, ignoring the status ones.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub counts: Option<Vec<StatusCount>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prev_cursor: Option<String>,
+}
+
+/// A todo with its subtasks nested below it.
+#[derive(Debug, Serialize)]
+pub struct TodoNode {
+    #[serde(flatten)]
+    pub todo: Todo,
+    pub children: Vec<TodoNode>,
+}
+
+impl TodoNode {
+    /// Nests `descendants` (every subtask below `root`) under their parents.
+    pub fn build(root: Todo, descendants: Vec<Todo>) -> Self {
+        let mut children: HashMap<i64, Vec<Todo>> = HashMap::new();
+        for todo in descendants {
+            if let Some(parent_id) = todo.parent_id {
+                children.entry(parent_id).or_default().push(todo);
+            }
+        }
+
+        Self::nest(root, &mut children)
+    }
+
+    fn nest(todo: Todo, children: &mut HashMap<i64, Vec<Todo>>) -> Self {
+        let nodes = children
+            .remove(&todo.id)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|child| Self::nest(child, children))
+            .collect();
+
+        Self {
+            todo,
+            children: nodes,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct TodoTreeResp {
+    pub tree: TodoNode,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct OccurrencesReq {
+    pub count: Option<usize>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct OccurrencesResp {
+    /// Due dates (start dates for todos without one) of the upcoming occurrences.
+    pub occurrences: Vec<DateTime<Utc>>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateCommentReq {
+    pub body: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateCommentReq {
+    pub body: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ListCommentsReq {
+    #[serde(default)]
+    pub offset: i64,
+    #[serde(default = "default_limit")]
+    pub limit: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CommentResp {
+    pub comment: Comment,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ListCommentsResp {
+    pub comments: Vec<Comment>,
+    pub has_more: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AttachmentResp {
+    pub attachment: Attachment,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ListAttachmentsResp {
+    pub attachments: Vec<Attachment>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ListHistoryReq {
+    #[serde(default)]
+    pub offset: i64,
+    #[serde(default = "default_limit")]
+    pub limit: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct HistoryResp {
+    pub events: Vec<TodoEvent>,
+    pub has_more: bool,
+}
+
+/// The todos a todo waits for and those waiting for it.
+#[derive(Debug, Serialize)]
+pub struct DependenciesResp {
+    pub blockers: Vec<Todo>,
+    pub dependents: Vec<Todo>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateTagReq {
+    pub name: String,
+    #[serde(default)]
+    pub color: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateTagReq {
+    pub name: Option<String>,
+    pub color: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TagResp {
+    pub tag: Tag,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ListTagsResp {
+    pub tags: Vec<Tag>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateStatusReq {
+    pub name: String,
+    #[serde(default)]
+    pub color: String,
+    /// After the other statuses when absent.
+    #[serde(default)]
+    pub position: Option<i32>,
+    #[serde(default)]
+    pub is_done: bool,
+    /// Ids or names of the statuses a todo may go to, any of them when absent.
+    #[serde(default)]
+    pub transitions: Option<Vec<StatusKey>>,
+}
+
+/// Body of `PATCH /statuses/:id`, `"transitions": null` allows every
+/// transition again.
+#[derive(Debug, Default, Deserialize)]
+pub struct UpdateStatusReq {
+    pub name: Option<String>,
+    pub color: Option<String>,
+    pub position: Option<i32>,
+    pub is_done: Option<bool>,
+    #[serde(default, deserialize_with = "deserialize_some")]
+    pub transitions: Option<Option<Vec<StatusKey>>>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct StatusResp {
+    pub status: Status,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ListStatusesResp {
+    pub statuses: Vec<Status>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateProjectReq {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub color: String,
+    #[serde(default)]
+    pub position: i32,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct UpdateProjectReq {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub color: Option<String>,
+    pub archived: Option<bool>,
+    pub position: Option<i32>,
+}
+
+impl From<UpdateProjectReq> for UpdateProject {
+    fn from(req: UpdateProjectReq) -> Self {
+        Self {
+            name: req.name.map(|name| name.trim().to_string()),
+            description: req.description,
+            color: req.color,
+            archived: req.archived,
+            position: req.position,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct ProjectResp {
+    pub project: Project,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ListProjectsResp {
+    pub projects: Vec<Project>,
 }
@@ -0,0 +1,154 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use tracing::info;
+
+use crate::{
+    errors::AppError,
+    models::status::{self, CreateStatus, Status, StatusKey, UpdateStatus},
+    AppState,
+};
+
+use super::{
+    extract::AuthUser,
+    request::{CreateStatusReq, ListStatusesResp, StatusResp, UpdateStatusReq},
+};
+
+const MAX_STATUS_NAME_LEN: usize = 64;
+
+#[axum::debug_handler]
+pub async fn create_status_handler(
+    AuthUser(user): AuthUser,
+    State(state): State<AppState>,
+    Json(req): Json<CreateStatusReq>,
+) -> Result<impl IntoResponse, AppError> {
+    info!("{:?}", req);
+
+    check_status_name(&req.name)?;
+    let statuses = status::get_statuses_by_user_id(user.id, &state.pool).await?;
+
+    let status = status::create_status(
+        &CreateStatus {
+            user_id: user.id,
+            name: req.name.trim().to_string(),
+            color: req.color,
+            position: req.position,
+            is_done: req.is_done,
+            transitions: resolve_transitions(req.transitions, &statuses)?,
+        },
+        &state.pool,
+    )
+    .await?;
+
+    Ok((StatusCode::OK, Json(StatusResp { status })).into_response())
+}
+
+#[axum::debug_handler]
+pub async fn list_statuses_handler(
+    AuthUser(user): AuthUser,
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, AppError> {
+    let statuses = status::get_statuses_by_user_id(user.id, &state.pool).await?;
+
+    Ok((StatusCode::OK, Json(ListStatusesResp { statuses })).into_response())
+}
+
+#[axum::debug_handler]
+pub async fn update_status_handler(
+    AuthUser(user): AuthUser,
+    State(state): State<AppState>,
+    Path(id): Path<i16>,
+    Json(req): Json<UpdateStatusReq>,
+) -> Result<impl IntoResponse, AppError> {
+    info!("id: {}, req: {:?}", id, req);
+
+    if let Some(name) = &req.name {
+        check_status_name(name)?;
+    }
+    let statuses = status::get_statuses_by_user_id(user.id, &state.pool).await?;
+    let transitions = match req.transitions {
+        Some(transitions) => Some(resolve_transitions(transitions, &statuses)?),
+        None => None,
+    };
+
+    let status = status::update_status(
+        user.id,
+        id,
+        &UpdateStatus {
+            name: req.name.map(|name| name.trim().to_string()),
+            color: req.color,
+            position: req.position,
+            is_done: req.is_done,
+            transitions,
+        },
+        &state.pool,
+    )
+    .await?;
+
+    Ok((StatusCode::OK, Json(StatusResp { status })).into_response())
+}
+
+#[axum::debug_handler]
+pub async fn delete_status_handler(
+    AuthUser(user): AuthUser,
+    State(state): State<AppState>,
+    Path(id): Path<i16>,
+) -> Result<impl IntoResponse, AppError> {
+    info!("id: {}", id);
+
+    status::delete_status(user.id, id, &state.pool).await?;
+
+    Ok(StatusCode::NO_CONTENT.into_response())
+}
+
+fn resolve_transitions(
+    transitions: Option<Vec<StatusKey>>,
+    statuses: &[Status],
+) -> Result<Option<Vec<i16>>, AppError> {
+    transitions
+        .map(|keys| keys.iter().map(|key| key.resolve(statuses)).collect())
+        .transpose()
+}
+
+/// Names are how clients refer to statuses in query strings, so they can't
+/// look like an id nor hold the `,` separating them there.
+fn check_status_name(name: &str) -> Result<(), AppError> {
+    let name = name.trim();
+    if name.is_empty() || name.chars().count() > MAX_STATUS_NAME_LEN {
+        return Err(AppError::InvalidArgument(format!(
+            "status name must be 1 to {} characters",
+            MAX_STATUS_NAME_LEN
+        )));
+    }
+    if name.contains(',') {
+        return Err(AppError::InvalidArgument(format!(
+            "status name can not contain a comma: {}",
+            name
+        )));
+    }
+    if name.parse::<i16>().is_ok() {
+        return Err(AppError::InvalidArgument(format!(
+            "status name can not be a number: {}",
+            name
+        )));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_status_name() {
+        assert!(check_status_name(" Blocked ").is_ok());
+        assert!(check_status_name("  ").is_err());
+        assert!(check_status_name("Blocked, hard").is_err());
+        assert!(check_status_name(" 12 ").is_err());
+        assert!(check_status_name(&"a".repeat(MAX_STATUS_NAME_LEN + 1)).is_err());
+    }
+}
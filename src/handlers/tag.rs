@@ -0,0 +1,160 @@
+use std::slice;
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use sqlx::PgExecutor;
+use tracing::info;
+
+use crate::{
+    errors::AppError,
+    models::{
+        tag::{self, CreateTag, Tag, UpdateTag},
+        todolist,
+    },
+    AppState,
+};
+
+use super::{
+    extract::AuthUser,
+    request::{CreateTagReq, CreateTodoResp, ListTagsResp, TagResp, UpdateTagReq},
+    todolist::get_user_todo,
+};
+
+const MAX_TAG_NAME_LEN: usize = 64;
+
+#[axum::debug_handler]
+pub async fn create_tag_handler(
+    AuthUser(user): AuthUser,
+    State(state): State<AppState>,
+    Json(req): Json<CreateTagReq>,
+) -> Result<impl IntoResponse, AppError> {
+    info!("{:?}", req);
+
+    check_tag_name(&req.name)?;
+
+    let tag = tag::create_tag(
+        &CreateTag {
+            user_id: user.id,
+            name: req.name.trim().to_string(),
+            color: req.color,
+        },
+        &state.pool,
+    )
+    .await?;
+
+    Ok((StatusCode::OK, Json(TagResp { tag })).into_response())
+}
+
+#[axum::debug_handler]
+pub async fn list_tags_handler(
+    AuthUser(user): AuthUser,
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, AppError> {
+    let tags = tag::get_tags_by_user_id(user.id, &state.pool).await?;
+
+    Ok((StatusCode::OK, Json(ListTagsResp { tags })).into_response())
+}
+
+#[axum::debug_handler]
+pub async fn update_tag_handler(
+    AuthUser(user): AuthUser,
+    State(state): State<AppState>,
+    Path(id): Path<u64>,
+    Json(req): Json<UpdateTagReq>,
+) -> Result<impl IntoResponse, AppError> {
+    info!("req: {:?}", req);
+
+    get_user_tag(id as i64, user.id, &state.pool).await?;
+    if let Some(name) = &req.name {
+        check_tag_name(name)?;
+    }
+
+    let tag = tag::update_tag(
+        id as i64,
+        user.id,
+        &UpdateTag {
+            name: req.name.map(|name| name.trim().to_string()),
+            color: req.color,
+        },
+        &state.pool,
+    )
+    .await?;
+
+    Ok((StatusCode::OK, Json(TagResp { tag })).into_response())
+}
+
+#[axum::debug_handler]
+pub async fn delete_tag_handler(
+    AuthUser(user): AuthUser,
+    State(state): State<AppState>,
+    Path(id): Path<u64>,
+) -> Result<impl IntoResponse, AppError> {
+    info!("id: {}", id);
+
+    get_user_tag(id as i64, user.id, &state.pool).await?;
+
+    tag::delete_tag(id as i64, &state.pool).await?;
+
+    Ok(StatusCode::NO_CONTENT.into_response())
+}
+
+#[axum::debug_handler]
+pub async fn attach_tag_handler(
+    AuthUser(user): AuthUser,
+    State(state): State<AppState>,
+    Path((id, tag_id)): Path<(u64, u64)>,
+) -> Result<impl IntoResponse, AppError> {
+    info!("id: {}, tag_id: {}", id, tag_id);
+
+    let mut todo = get_user_todo(id as i64, user.id, &state.pool).await?;
+    get_user_tag(tag_id as i64, user.id, &state.pool).await?;
+
+    tag::attach_tag(todo.id, tag_id as i64, &state.pool).await?;
+    todolist::load_relations(slice::from_mut(&mut todo), &state.pool).await?;
+
+    Ok((StatusCode::OK, Json(CreateTodoResp { todo })).into_response())
+}
+
+#[axum::debug_handler]
+pub async fn detach_tag_handler(
+    AuthUser(user): AuthUser,
+    State(state): State<AppState>,
+    Path((id, tag_id)): Path<(u64, u64)>,
+) -> Result<impl IntoResponse, AppError> {
+    info!("id: {}, tag_id: {}", id, tag_id);
+
+    let mut todo = get_user_todo(id as i64, user.id, &state.pool).await?;
+
+    tag::detach_tag(todo.id, tag_id as i64, &state.pool).await?;
+    todolist::load_relations(slice::from_mut(&mut todo), &state.pool).await?;
+
+    Ok((StatusCode::OK, Json(CreateTodoResp { todo })).into_response())
+}
+
+pub(crate) async fn get_user_tag<'c>(
+    id: i64,
+    user_id: i64,
+    executor: impl PgExecutor<'c>,
+) -> Result<Tag, AppError> {
+    let tag = tag::get_tag_by_id(id, user_id, executor)
+        .await?
+        .ok_or_else(|| AppError::TagNotExists(format!("tag: {} not found", id)))?;
+
+    Ok(tag)
+}
+
+fn check_tag_name(name: &str) -> Result<(), AppError> {
+    let name = name.trim();
+    if name.is_empty() || name.chars().count() > MAX_TAG_NAME_LEN {
+        return Err(AppError::InvalidArgument(format!(
+            "tag name must be 1 to {} characters",
+            MAX_TAG_NAME_LEN
+        )));
+    }
+
+    Ok(())
+}
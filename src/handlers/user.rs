@@ -1,14 +1,22 @@
 use axum::{extract::State, http::StatusCode, response::IntoResponse, Json};
+use chrono::{DateTime, Duration, Utc};
 use tracing::info;
 
 use crate::{
+    auth,
     errors::AppError,
     handlers::utils,
-    models::user::{self, CreateUser},
+    models::{
+        refresh_token, revocation,
+        user::{self, CreateUser},
+    },
     AppState,
 };
 
-use super::request::{LoginReq, LoginResp, SignupReq, SignupResp};
+use super::{
+    extract::{AuthClaims, AuthUser},
+    request::{LoginReq, LoginResp, RefreshTokenReq, SignupReq, SignupResp},
+};
 
 #[axum::debug_handler]
 pub async fn signup_handler(
@@ -25,10 +33,20 @@ pub async fn signup_handler(
     };
 
     let user = user::create_user(&create_user, &state.pool).await?;
-    let token = state.auth.sign(user.clone())?;
+    let (refresh_token, family_id) = new_refresh_token(user.id, &state).await?;
+    let token = state.auth.sign(user.clone(), Some(family_id))?;
     info!("create user: {:?}", user);
 
-    Ok((StatusCode::OK, Json(SignupResp { user, token })).into_response())
+    Ok((
+        StatusCode::OK,
+        Json(SignupResp {
+            user,
+            token,
+            refresh_token,
+            expires_in: state.auth.access_ttl,
+        }),
+    )
+        .into_response())
 }
 
 #[axum::debug_handler]
@@ -55,6 +73,111 @@ pub async fn signin_handler(
         )));
     }
 
-    let token = state.auth.sign(user)?;
-    Ok((StatusCode::OK, Json(LoginResp { token })).into_response())
+    let (refresh_token, family_id) = new_refresh_token(user.id, &state).await?;
+    let token = state.auth.sign(user, Some(family_id))?;
+    Ok((
+        StatusCode::OK,
+        Json(LoginResp {
+            token,
+            refresh_token,
+            expires_in: state.auth.access_ttl,
+        }),
+    )
+        .into_response())
+}
+
+/// Trades a refresh token for a new access token and the next refresh token,
+/// the old one can't be used again.
+#[axum::debug_handler]
+pub async fn refresh_token_handler(
+    State(state): State<AppState>,
+    Json(req): Json<RefreshTokenReq>,
+) -> Result<impl IntoResponse, AppError> {
+    let refresh_token = auth::gen_refresh_token();
+    let rotated = refresh_token::rotate_refresh_token(
+        &auth::hash_token(&req.refresh_token),
+        &auth::hash_token(&refresh_token),
+        refresh_expiry(&state),
+        &state.pool,
+    )
+    .await?;
+
+    let user = user::get_user_by_id(rotated.user_id, &state.pool)
+        .await?
+        .ok_or_else(|| AppError::AuthFailed(format!("user: {} not found", rotated.user_id)))?;
+    let token = state.auth.sign(user, Some(rotated.family_id))?;
+
+    Ok((
+        StatusCode::OK,
+        Json(LoginResp {
+            token,
+            refresh_token,
+            expires_in: state.auth.access_ttl,
+        }),
+    )
+        .into_response())
+}
+
+/// Revokes the access token of the request along with the family of the
+/// refresh token it was issued with.
+#[axum::debug_handler]
+pub async fn signout_handler(
+    AuthUser(user): AuthUser,
+    AuthClaims(claims): AuthClaims,
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, AppError> {
+    let Some(jti) = &claims.jwt_id else {
+        return Err(AppError::InvalidArgument(
+            "token has no id, use /signout-all".to_string(),
+        ));
+    };
+    let expires_at = claims
+        .expires_at
+        .and_then(|exp| DateTime::from_timestamp(exp.as_secs() as i64, 0))
+        .unwrap_or_else(|| Utc::now() + Duration::seconds(state.auth.access_ttl as i64));
+
+    let revocation = revocation::revoke_token(user.id, jti, expires_at, &state.pool).await?;
+    state.auth.revocations().add(revocation);
+    if let Some(family_id) = claims.custom.family_id {
+        refresh_token::revoke_refresh_family(family_id, user.id, &state.pool).await?;
+    }
+    info!("user: {} signed out", user.id);
+
+    Ok(StatusCode::NO_CONTENT.into_response())
+}
+
+/// Revokes every access and refresh token of the user, on all devices.
+#[axum::debug_handler]
+pub async fn signout_all_handler(
+    AuthUser(user): AuthUser,
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, AppError> {
+    // tokens issued until now are all expired by then
+    let revoked_at = Utc::now();
+    let expires_at = revoked_at + Duration::seconds(state.auth.access_ttl as i64);
+    let revocation =
+        revocation::revoke_user_tokens(user.id, revoked_at, expires_at, &state.pool).await?;
+    state.auth.revocations().add(revocation);
+    info!("user: {} signed out everywhere", user.id);
+
+    Ok(StatusCode::NO_CONTENT.into_response())
+}
+
+/// Starts a new family of refresh tokens for the user, returns the token and
+/// the family id.
+async fn new_refresh_token(user_id: i64, state: &AppState) -> Result<(String, i64), AppError> {
+    let token = auth::gen_refresh_token();
+    let created = refresh_token::create_refresh_token(
+        user_id,
+        &auth::hash_token(&token),
+        refresh_expiry(state),
+        &state.pool,
+    )
+    .await?;
+
+    Ok((token, created.family_id))
+}
+
+fn refresh_expiry(state: &AppState) -> DateTime<Utc> {
+    Utc::now() + Duration::seconds(state.auth.refresh_ttl as i64)
 }
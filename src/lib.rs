@@ -1,10 +1,12 @@
 use auth::Authorization;
-use config::AppConfig;
+use config::{AppConfig, StorageConfig};
 use errors::AppError;
 use route::get_route;
 use sqlx::PgPool;
-use std::{ops::Deref, sync::Arc};
-use tokio::net::TcpListener;
+use std::{ops::Deref, sync::Arc, time::Duration};
+use storage::{LocalStorage, Storage};
+use tokio::{net::TcpListener, time};
+use tracing::warn;
 
 pub mod auth;
 pub mod config;
@@ -12,9 +14,15 @@ pub mod errors;
 pub mod handlers;
 pub mod models;
 pub mod route;
+pub mod storage;
+
+/// How often revocations made by other instances are picked up, and expired
+/// revocations and refresh tokens deleted.
+const REVOCATION_SYNC_INTERVAL: Duration = Duration::from_secs(60);
 
 pub async fn run(state: AppState, conf: &AppConfig) -> anyhow::Result<()> {
     println!("app-todolist start...");
+    tokio::spawn(sync_revocations(state.clone()));
 
     let addr = format!("0.0.0.0:{}", conf.server.port);
     let listener = TcpListener::bind(&addr).await?;
@@ -34,10 +42,17 @@ pub struct AppState {
 impl AppState {
     pub async fn new(conf: &AppConfig) -> Result<Self, AppError> {
         let pool = PgPool::connect(&conf.server.db_url).await?;
-        let auth = Authorization::new(&conf.auth.private_key, &conf.auth.public_key)?;
+        let auth = Authorization::new(&conf.auth)?;
+        auth.revocations().sync(&pool).await?;
+        let storage = Arc::new(LocalStorage::new(&conf.storage.root));
 
         Ok(Self {
-            state: Arc::new(InnerAppState { pool, auth }),
+            state: Arc::new(InnerAppState {
+                pool,
+                auth,
+                storage,
+                storage_config: conf.storage.clone(),
+            }),
         })
     }
 }
@@ -45,6 +60,24 @@ impl AppState {
 pub struct InnerAppState {
     pub(crate) pool: PgPool,
     pub(crate) auth: Authorization,
+    pub(crate) storage: Arc<dyn Storage>,
+    pub(crate) storage_config: StorageConfig,
+}
+
+async fn sync_revocations(state: AppState) {
+    let mut interval = time::interval(REVOCATION_SYNC_INTERVAL);
+    loop {
+        interval.tick().await;
+        if let Err(e) = models::revocation::delete_expired_revocations(&state.pool).await {
+            warn!("failed to delete expired revocations: {}", e);
+        }
+        if let Err(e) = models::refresh_token::delete_expired_refresh_tokens(&state.pool).await {
+            warn!("failed to delete expired refresh tokens: {}", e);
+        }
+        if let Err(e) = state.auth.revocations().sync(&state.pool).await {
+            warn!("failed to sync revocations: {}", e);
+        }
+    }
 }
 
 impl Deref for AppState {
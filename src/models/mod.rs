@@ -1,2 +1,15 @@
+pub mod attachment;
+pub mod comment;
+pub mod dependency;
+pub mod event;
+pub mod personal_token;
+pub mod project;
+pub mod rank;
+pub mod recurrence;
+pub mod refresh_token;
+pub mod revocation;
+pub mod search;
+pub mod status;
+pub mod tag;
 pub mod todolist;
 pub mod user;
@@ -0,0 +1,131 @@
+/// Digits of a rank, in the order the database compares them (`COLLATE "C"`).
+const DIGITS: &[u8; 36] = b"0123456789abcdefghijklmnopqrstuvwxyz";
+const BASE: u32 = DIGITS.len() as u32;
+
+/// Longest rank handed out, a list whose ranks grow past it is rebalanced.
+pub const MAX_RANK_LEN: usize = 16;
+
+/// Ranks are base 36 fractions, `"i"` is 18/36 and `"i8"` 18/36 + 8/36².
+/// They compare as strings the same way as the fractions they stand for as
+/// long as none ends with a `0`, which none of the functions below produce.
+fn digit(rank: &[u8], i: usize) -> u32 {
+    rank.get(i)
+        .and_then(|d| DIGITS.iter().position(|c| c == d))
+        .unwrap_or_default() as u32
+}
+
+/// A rank sorting after `lo` and before `hi`, `None` standing for the start
+/// and the end of the list. Returns `None` when `lo` does not sort before `hi`.
+pub fn rank_between(lo: Option<&str>, hi: Option<&str>) -> Option<String> {
+    let lo = lo.unwrap_or_default().as_bytes();
+    let mut hi = hi.map(str::as_bytes);
+    if hi.is_some_and(|hi| lo >= hi) {
+        return None;
+    }
+
+    let mut rank = vec![];
+    let mut i = 0;
+    loop {
+        let l = digit(lo, i);
+        let h = match hi {
+            // nothing fits below the end of a rank ending in 0
+            Some(hi) if i >= hi.len() => return None,
+            Some(hi) => digit(hi, i),
+            None => BASE,
+        };
+        if h > l + 1 {
+            rank.push(DIGITS[((l + h) / 2) as usize]);
+            break;
+        }
+
+        // once below `hi` on this digit, anything can follow
+        rank.push(DIGITS[l as usize]);
+        if h > l {
+            hi = None;
+        }
+        i += 1;
+    }
+
+    Some(String::from_utf8(rank).expect("ranks are ascii"))
+}
+
+/// `count` ranks spread evenly, to rebalance a list whose ranks got too long.
+pub fn spread(count: usize) -> Vec<String> {
+    let slots = count as u128 + 1;
+    // leave room for a few moves between every two todos
+    let mut width = 1;
+    let mut space = BASE as u128;
+    while space < slots * BASE as u128 {
+        width += 1;
+        space *= BASE as u128;
+    }
+    let step = space / slots;
+
+    (1..slots)
+        .map(|i| {
+            let mut value = i * step;
+            let mut rank = vec![b'0'; width];
+            for d in rank.iter_mut().rev() {
+                *d = DIGITS[(value % BASE as u128) as usize];
+                value /= BASE as u128;
+            }
+            while rank.last() == Some(&b'0') {
+                rank.pop();
+            }
+            String::from_utf8(rank).expect("ranks are ascii")
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rank_between() {
+        assert_eq!(rank_between(None, None), Some("i".to_string()));
+        assert_eq!(rank_between(Some("i"), None), Some("r".to_string()));
+        assert_eq!(rank_between(None, Some("i")), Some("9".to_string()));
+        assert_eq!(rank_between(Some("a"), Some("b")), Some("ai".to_string()));
+        assert_eq!(rank_between(Some("a"), Some("a5")), Some("a2".to_string()));
+        assert_eq!(rank_between(Some("az"), Some("b")), Some("azi".to_string()));
+        assert_eq!(rank_between(Some("z"), None), Some("zi".to_string()));
+        assert_eq!(rank_between(None, Some("01")), Some("00i".to_string()));
+
+        assert_eq!(rank_between(Some("b"), Some("a")), None);
+        assert_eq!(rank_between(Some("a"), Some("a")), None);
+        assert_eq!(rank_between(Some("a"), Some("a0")), None);
+    }
+
+    #[test]
+    fn test_rank_between_keeps_order() {
+        // always inserting at the same spot
+        let mut ranks = vec!["i".to_string(), "j".to_string()];
+        for _ in 0..100 {
+            let rank = rank_between(Some(&ranks[0]), Some(&ranks[1])).unwrap();
+            ranks.insert(1, rank);
+        }
+        for _ in 0..100 {
+            let rank = rank_between(None, Some(&ranks[0])).unwrap();
+            ranks.insert(0, rank);
+        }
+        let mut sorted = ranks.clone();
+        sorted.sort();
+        assert_eq!(ranks, sorted);
+        assert!(ranks.iter().all(|r| !r.ends_with('0')));
+    }
+
+    #[test]
+    fn test_spread() {
+        assert_eq!(spread(0), Vec::<String>::new());
+        assert_eq!(spread(1), vec!["i".to_string()]);
+
+        let ranks = spread(1000);
+        assert_eq!(ranks.len(), 1000);
+        assert!(ranks.windows(2).all(|w| w[0] < w[1]));
+        assert!(ranks.iter().all(|r| r.len() <= 3 && !r.ends_with('0')));
+        assert!(ranks
+            .windows(2)
+            .all(|w| rank_between(Some(&w[0]), Some(&w[1])).is_some()));
+    }
+}
@@ -0,0 +1,346 @@
+use std::{fmt, str::FromStr};
+
+use chrono::{DateTime, Datelike, Duration, Months, NaiveDate, TimeZone, Utc, Weekday};
+
+use crate::errors::AppError;
+
+const UNTIL_FORMAT: &str = "%Y%m%dT%H%M%SZ";
+
+/// A subset of the RFC 5545 RRULE: `FREQ` (daily, weekly, monthly, yearly),
+/// `INTERVAL`, `BYDAY` for weekly rules, `BYMONTHDAY` for monthly rules,
+/// `UNTIL` and `COUNT`.
+///
+/// Occurrences are computed in UTC. `COUNT` is the number of occurrences left
+/// including the current one, it goes down by one every time the series moves
+/// on to the next todo.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecurrenceRule {
+    pub freq: Frequency,
+    pub interval: u32,
+    pub by_day: Vec<Weekday>,
+    pub by_month_day: Option<u32>,
+    pub until: Option<DateTime<Utc>>,
+    pub count: Option<u32>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Frequency {
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+impl RecurrenceRule {
+    /// The first occurrence strictly after `current`, which is assumed to be an
+    /// occurrence itself. `None` once the series is over.
+    pub fn next_after(&self, current: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        if self.count.is_some_and(|count| count <= 1) {
+            return None;
+        }
+
+        let interval = self.interval.max(1);
+        let date = current.date_naive();
+        let next = match self.freq {
+            Frequency::Daily => date.checked_add_signed(Duration::days(interval as i64)),
+            Frequency::Weekly if self.by_day.is_empty() => {
+                date.checked_add_signed(Duration::weeks(interval as i64))
+            }
+            Frequency::Weekly => self.next_weekday(date, interval),
+            Frequency::Monthly => {
+                let day = self.by_month_day.unwrap_or_else(|| date.day());
+                Self::next_month_day(date, interval, day)
+            }
+            Frequency::Yearly => Self::next_year_day(date, interval),
+        }?;
+
+        let next = Utc.from_utc_datetime(&next.and_time(current.time()));
+        match self.until {
+            Some(until) if next > until => None,
+            _ => Some(next),
+        }
+    }
+
+    /// Up to `n` occurrences following `current`.
+    pub fn preview(&self, current: DateTime<Utc>, n: usize) -> Vec<DateTime<Utc>> {
+        let mut rule = self.clone();
+        let mut current = current;
+        let mut occurrences = vec![];
+        while occurrences.len() < n {
+            let Some(next) = rule.next_after(current) else {
+                break;
+            };
+            occurrences.push(next);
+            current = next;
+            rule = rule.advance();
+        }
+
+        occurrences
+    }
+
+    /// The rule carried by the next todo of the series.
+    pub fn advance(&self) -> Self {
+        Self {
+            count: self.count.map(|count| count.saturating_sub(1)),
+            ..self.clone()
+        }
+    }
+
+    fn next_weekday(&self, date: NaiveDate, interval: u32) -> Option<NaiveDate> {
+        let week_start = date.week(Weekday::Mon).first_day();
+        // a full cycle of `interval` weeks always contains every weekday
+        for offset in 1..=(7 * interval as i64 + 7) {
+            let candidate = date.checked_add_signed(Duration::days(offset))?;
+            let weeks = (candidate - week_start).num_days() / 7;
+            if weeks % interval as i64 == 0 && self.by_day.contains(&candidate.weekday()) {
+                return Some(candidate);
+            }
+        }
+
+        None
+    }
+
+    fn next_month_day(date: NaiveDate, interval: u32, day: u32) -> Option<NaiveDate> {
+        let first = date.with_day(1)?;
+        // months without the day (e.g. the 31st) are skipped, like RFC 5545 does
+        for step in 1..=12 {
+            let month = first.checked_add_months(Months::new(interval * step))?;
+            if let Some(next) = month.with_day(day) {
+                return Some(next);
+            }
+        }
+
+        None
+    }
+
+    fn next_year_day(date: NaiveDate, interval: u32) -> Option<NaiveDate> {
+        // Feb 29 only comes back on leap years
+        for step in 1..=8 {
+            let year = date.year() + (interval * step) as i32;
+            if let Some(next) = NaiveDate::from_ymd_opt(year, date.month(), date.day()) {
+                return Some(next);
+            }
+        }
+
+        None
+    }
+}
+
+impl FromStr for RecurrenceRule {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = |msg: &str| AppError::InvalidArgument(format!("recurrence: {}", msg));
+
+        let s = s.trim();
+        let s = s.strip_prefix("RRULE:").unwrap_or(s);
+
+        let mut freq = None;
+        let mut rule = RecurrenceRule {
+            freq: Frequency::Daily,
+            interval: 1,
+            by_day: vec![],
+            by_month_day: None,
+            until: None,
+            count: None,
+        };
+
+        for part in s.split(';').filter(|p| !p.is_empty()) {
+            let (key, value) = part
+                .split_once('=')
+                .ok_or_else(|| invalid(&format!("malformed part {}", part)))?;
+
+            match key.to_ascii_uppercase().as_str() {
+                "FREQ" => {
+                    freq = Some(match value.to_ascii_uppercase().as_str() {
+                        "DAILY" => Frequency::Daily,
+                        "WEEKLY" => Frequency::Weekly,
+                        "MONTHLY" => Frequency::Monthly,
+                        "YEARLY" => Frequency::Yearly,
+                        _ => return Err(invalid(&format!("unsupported FREQ {}", value))),
+                    })
+                }
+                "INTERVAL" => {
+                    rule.interval = value
+                        .parse()
+                        .ok()
+                        .filter(|i| (1..=366).contains(i))
+                        .ok_or_else(|| invalid("INTERVAL must be 1 to 366"))?;
+                }
+                "BYDAY" => {
+                    rule.by_day = value
+                        .split(',')
+                        .map(parse_weekday)
+                        .collect::<Option<Vec<_>>>()
+                        .ok_or_else(|| invalid(&format!("bad BYDAY {}", value)))?;
+                }
+                "BYMONTHDAY" => {
+                    rule.by_month_day = Some(
+                        value
+                            .parse()
+                            .ok()
+                            .filter(|d| (1..=31).contains(d))
+                            .ok_or_else(|| invalid("BYMONTHDAY must be 1 to 31"))?,
+                    );
+                }
+                "UNTIL" => {
+                    let until = chrono::NaiveDateTime::parse_from_str(value, UNTIL_FORMAT)
+                        .or_else(|_| {
+                            NaiveDate::parse_from_str(value, "%Y%m%d")
+                                .map(|d| d.and_hms_opt(23, 59, 59).unwrap_or_default())
+                        })
+                        .map_err(|_| invalid(&format!("bad UNTIL {}", value)))?;
+                    rule.until = Some(Utc.from_utc_datetime(&until));
+                }
+                "COUNT" => {
+                    rule.count = Some(
+                        value
+                            .parse()
+                            .ok()
+                            .filter(|c| *c >= 1)
+                            .ok_or_else(|| invalid("COUNT must be at least 1"))?,
+                    );
+                }
+                _ => return Err(invalid(&format!("unsupported part {}", key))),
+            }
+        }
+
+        rule.freq = freq.ok_or_else(|| invalid("FREQ is required"))?;
+        if !rule.by_day.is_empty() && rule.freq != Frequency::Weekly {
+            return Err(invalid("BYDAY is only supported with FREQ=WEEKLY"));
+        }
+        if rule.by_month_day.is_some() && rule.freq != Frequency::Monthly {
+            return Err(invalid("BYMONTHDAY is only supported with FREQ=MONTHLY"));
+        }
+        if rule.until.is_some() && rule.count.is_some() {
+            return Err(invalid("UNTIL and COUNT can not be used together"));
+        }
+
+        Ok(rule)
+    }
+}
+
+impl fmt::Display for RecurrenceRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let freq = match self.freq {
+            Frequency::Daily => "DAILY",
+            Frequency::Weekly => "WEEKLY",
+            Frequency::Monthly => "MONTHLY",
+            Frequency::Yearly => "YEARLY",
+        };
+        write!(f, "FREQ={}", freq)?;
+
+        if self.interval > 1 {
+            write!(f, ";INTERVAL={}", self.interval)?;
+        }
+        if !self.by_day.is_empty() {
+            let days: Vec<&str> = self.by_day.iter().map(weekday_code).collect();
+            write!(f, ";BYDAY={}", days.join(","))?;
+        }
+        if let Some(day) = self.by_month_day {
+            write!(f, ";BYMONTHDAY={}", day)?;
+        }
+        if let Some(until) = self.until {
+            write!(f, ";UNTIL={}", until.format(UNTIL_FORMAT))?;
+        }
+        if let Some(count) = self.count {
+            write!(f, ";COUNT={}", count)?;
+        }
+
+        Ok(())
+    }
+}
+
+fn parse_weekday(code: &str) -> Option<Weekday> {
+    match code.trim().to_ascii_uppercase().as_str() {
+        "MO" => Some(Weekday::Mon),
+        "TU" => Some(Weekday::Tue),
+        "WE" => Some(Weekday::Wed),
+        "TH" => Some(Weekday::Thu),
+        "FR" => Some(Weekday::Fri),
+        "SA" => Some(Weekday::Sat),
+        "SU" => Some(Weekday::Sun),
+        _ => None,
+    }
+}
+
+fn weekday_code(day: &Weekday) -> &'static str {
+    match day {
+        Weekday::Mon => "MO",
+        Weekday::Tue => "TU",
+        Weekday::Wed => "WE",
+        Weekday::Thu => "TH",
+        Weekday::Fri => "FR",
+        Weekday::Sat => "SA",
+        Weekday::Sun => "SU",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Result;
+
+    fn utc(y: i32, m: u32, d: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(y, m, d, 9, 30, 0).unwrap()
+    }
+
+    #[test]
+    fn test_parse_and_display_rule() -> Result<()> {
+        let rule: RecurrenceRule = "RRULE:FREQ=weekly;INTERVAL=2;BYDAY=MO,FR;COUNT=4".parse()?;
+        assert_eq!(rule.freq, Frequency::Weekly);
+        assert_eq!(rule.interval, 2);
+        assert_eq!(rule.by_day, vec![Weekday::Mon, Weekday::Fri]);
+        assert_eq!(rule.count, Some(4));
+        assert_eq!(
+            rule.to_string(),
+            "FREQ=WEEKLY;INTERVAL=2;BYDAY=MO,FR;COUNT=4"
+        );
+
+        assert!("INTERVAL=2".parse::<RecurrenceRule>().is_err());
+        assert!("FREQ=HOURLY".parse::<RecurrenceRule>().is_err());
+        assert!("FREQ=DAILY;BYDAY=MO".parse::<RecurrenceRule>().is_err());
+        assert!("FREQ=DAILY;COUNT=2;UNTIL=20250101"
+            .parse::<RecurrenceRule>()
+            .is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_next_daily_and_weekly() -> Result<()> {
+        let rule: RecurrenceRule = "FREQ=DAILY;INTERVAL=3".parse()?;
+        assert_eq!(rule.next_after(utc(2024, 11, 30)), Some(utc(2024, 12, 3)));
+
+        // 2024-11-18 is a Monday
+        let rule: RecurrenceRule = "FREQ=WEEKLY;INTERVAL=2;BYDAY=MO,FR".parse()?;
+        assert_eq!(
+            rule.preview(utc(2024, 11, 18), 3),
+            vec![utc(2024, 11, 22), utc(2024, 12, 2), utc(2024, 12, 6)]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_next_monthly_and_yearly() -> Result<()> {
+        let rule: RecurrenceRule = "FREQ=MONTHLY;BYMONTHDAY=31".parse()?;
+        assert_eq!(
+            rule.preview(utc(2024, 1, 31), 2),
+            vec![utc(2024, 3, 31), utc(2024, 5, 31)]
+        );
+
+        let rule: RecurrenceRule = "FREQ=YEARLY".parse()?;
+        assert_eq!(rule.next_after(utc(2024, 2, 29)), Some(utc(2028, 2, 29)));
+        Ok(())
+    }
+
+    #[test]
+    fn test_until_and_count_end_series() -> Result<()> {
+        let rule: RecurrenceRule = "FREQ=DAILY;UNTIL=20241202T000000Z".parse()?;
+        assert_eq!(rule.preview(utc(2024, 11, 30), 5), vec![utc(2024, 12, 1)]);
+
+        let rule: RecurrenceRule = "FREQ=DAILY;COUNT=3".parse()?;
+        assert_eq!(rule.preview(utc(2024, 11, 30), 5).len(), 2);
+        assert_eq!(rule.advance().advance().next_after(utc(2024, 12, 2)), None);
+        Ok(())
+    }
+}
@@ -2,7 +2,10 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::{FromRow, PgPool};
 
-use crate::errors::AppError;
+use crate::{
+    errors::AppError,
+    models::{project, status},
+};
 
 #[derive(Debug, Clone, FromRow, Serialize, Deserialize, PartialEq)]
 pub struct User {
@@ -27,18 +30,20 @@ pub struct CreateUser {
 pub async fn create_user(input: &CreateUser, pool: &PgPool) -> Result<User, AppError> {
     let user_result = get_user_by_email(&input.email, pool).await?;
     if user_result.is_some() {
-        return Err(AppError::EmailAlreadyExists(format!(
-            "{}",
-            input.email.clone()
-        )));
+        return Err(AppError::EmailAlreadyExists(input.email.clone()));
     }
 
-    let user = sqlx::query_as("INSERT INTO users(username, email, password_hash) VALUES($1, $2, $3) RETURNING id,username,email,created_at,updated_at")
+    let mut tx = pool.begin().await?;
+    let user: User = sqlx::query_as("INSERT INTO users(username, email, password_hash) VALUES($1, $2, $3) RETURNING id,username,email,created_at,updated_at")
         .bind(&input.username)
         .bind(&input.email)
         .bind(&input.password_hash)
-        .fetch_one(pool)
+        .fetch_one(&mut *tx)
         .await?;
+    status::seed_statuses(user.id, &mut tx).await?;
+    project::create_inbox_project(user.id, &mut tx).await?;
+    tx.commit().await?;
+
     Ok(user)
 }
 
@@ -89,7 +94,7 @@ mod tests {
         assert_eq!(user.email, "bob@acme.org".to_string());
         assert_eq!(user.password_hash, None);
 
-        sqlx::query("TRUNCATE TABLE users,todos;")
+        sqlx::query("TRUNCATE TABLE users,todos CASCADE;")
             .execute(&pool)
             .await?;
 
@@ -118,7 +123,7 @@ mod tests {
         assert!(user2.is_some());
         assert_eq!(user, user2.unwrap());
 
-        sqlx::query("TRUNCATE TABLE users,todos;")
+        sqlx::query("TRUNCATE TABLE users,todos CASCADE;")
             .execute(&pool)
             .await?;
 
@@ -147,7 +152,7 @@ mod tests {
         assert!(user2.is_some());
         assert_eq!(user, user2.unwrap());
 
-        sqlx::query("TRUNCATE TABLE users,todos;")
+        sqlx::query("TRUNCATE TABLE users,todos CASCADE;")
             .execute(&pool)
             .await?;
 
@@ -1,22 +1,147 @@
 use axum::{
-    routing::{get, post},
+    extract::DefaultBodyLimit,
+    middleware,
+    routing::{delete, get, patch, post},
     Router,
 };
 
 use crate::{
     handlers::{
-        todolist::{create_todo_handler, get_todo_handler, list_todo_handler, update_todo_handler},
-        user::{signin_handler, signup_handler},
+        attachment::{
+            delete_attachment_handler, download_attachment_handler, list_attachments_handler,
+            upload_attachment_handler,
+        },
+        board::get_board_handler,
+        comment::{
+            create_comment_handler, delete_comment_handler, list_comments_handler,
+            update_comment_handler,
+        },
+        dependency::{
+            add_dependency_handler, list_dependencies_handler, remove_dependency_handler,
+        },
+        extract::require_auth,
+        personal_token::{
+            create_personal_token_handler, list_personal_tokens_handler,
+            revoke_personal_token_handler,
+        },
+        project::{
+            create_project_handler, delete_project_handler, get_project_handler,
+            list_projects_handler, update_project_handler,
+        },
+        status::{
+            create_status_handler, delete_status_handler, list_statuses_handler,
+            update_status_handler,
+        },
+        tag::{
+            attach_tag_handler, create_tag_handler, delete_tag_handler, detach_tag_handler,
+            list_tags_handler, update_tag_handler,
+        },
+        todolist::{
+            bulk_todos_handler, create_todo_handler, delete_todo_handler, get_todo_handler,
+            get_todo_history_handler, get_todo_tree_handler, list_children_handler,
+            list_todo_handler, list_trash_handler, move_todo_handler, patch_todo_handler,
+            preview_occurrences_handler, purge_todo_handler, restore_todo_handler,
+            search_todos_handler, update_todo_handler,
+        },
+        user::{
+            refresh_token_handler, signin_handler, signout_all_handler, signout_handler,
+            signup_handler,
+        },
     },
     AppState,
 };
 
+/// Room for the multipart framing around an uploaded file.
+const MULTIPART_OVERHEAD: usize = 64 * 1024;
+
 pub fn get_route(state: AppState) -> Router {
-    let route = Router::new()
+    let upload_limit = state.storage_config.max_file_size as usize + MULTIPART_OVERHEAD;
+
+    // everything but signing in needs a user, personal access tokens only
+    // reach the todo routes their scopes allow
+    let protected = Router::new()
         .route("/todos", post(create_todo_handler).get(list_todo_handler))
-        .route("/todos/:id", get(get_todo_handler).put(update_todo_handler))
+        .route("/todos/trash", get(list_trash_handler))
+        .route("/todos/bulk", post(bulk_todos_handler))
+        .route("/todos/search", get(search_todos_handler))
+        .route(
+            "/todos/:id",
+            get(get_todo_handler)
+                .put(update_todo_handler)
+                .patch(patch_todo_handler)
+                .delete(delete_todo_handler),
+        )
+        .route("/todos/:id/children", get(list_children_handler))
+        .route("/todos/:id/tree", get(get_todo_tree_handler))
+        .route("/todos/:id/history", get(get_todo_history_handler))
+        .route("/todos/:id/occurrences", get(preview_occurrences_handler))
+        .route(
+            "/todos/:id/comments",
+            post(create_comment_handler).get(list_comments_handler),
+        )
+        .route(
+            "/todos/:id/comments/:cid",
+            patch(update_comment_handler).delete(delete_comment_handler),
+        )
+        .route(
+            "/todos/:id/attachments",
+            post(upload_attachment_handler)
+                .layer(DefaultBodyLimit::max(upload_limit))
+                .get(list_attachments_handler),
+        )
+        .route(
+            "/todos/:id/attachments/:aid",
+            get(download_attachment_handler).delete(delete_attachment_handler),
+        )
+        .route("/todos/:id/move", post(move_todo_handler))
+        .route("/todos/:id/restore", post(restore_todo_handler))
+        .route("/todos/:id/purge", delete(purge_todo_handler))
+        .route("/todos/:id/dependencies", get(list_dependencies_handler))
+        .route(
+            "/todos/:id/dependencies/:blocker_id",
+            post(add_dependency_handler).delete(remove_dependency_handler),
+        )
+        .route(
+            "/todos/:id/tags/:tag_id",
+            post(attach_tag_handler).delete(detach_tag_handler),
+        )
+        .route("/board", get(get_board_handler))
+        .route(
+            "/statuses",
+            post(create_status_handler).get(list_statuses_handler),
+        )
+        .route(
+            "/statuses/:id",
+            patch(update_status_handler).delete(delete_status_handler),
+        )
+        .route("/tags", post(create_tag_handler).get(list_tags_handler))
+        .route(
+            "/tags/:id",
+            patch(update_tag_handler).delete(delete_tag_handler),
+        )
+        .route(
+            "/projects",
+            post(create_project_handler).get(list_projects_handler),
+        )
+        .route(
+            "/projects/:id",
+            get(get_project_handler)
+                .patch(update_project_handler)
+                .delete(delete_project_handler),
+        )
+        .route(
+            "/tokens",
+            post(create_personal_token_handler).get(list_personal_tokens_handler),
+        )
+        .route("/tokens/:id", delete(revoke_personal_token_handler))
+        .route("/signout", post(signout_handler))
+        .route("/signout-all", post(signout_all_handler))
+        .route_layer(middleware::from_fn_with_state(state.clone(), require_auth));
+
+    Router::new()
         .route("/signup", post(signup_handler))
         .route("/signin", post(signin_handler))
-        .with_state(state);
-    route
+        .route("/token/refresh", post(refresh_token_handler))
+        .merge(protected)
+        .with_state(state)
 }
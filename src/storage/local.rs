@@ -0,0 +1,94 @@
+use std::{
+    io::ErrorKind,
+    path::{Component, Path, PathBuf},
+};
+
+use axum::{async_trait, body::Bytes};
+use tokio::fs;
+
+use crate::{errors::AppError, storage::Storage};
+
+/// Keeps every object as a file under a root directory.
+#[derive(Debug, Clone)]
+pub struct LocalStorage {
+    root: PathBuf,
+}
+
+impl LocalStorage {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    /// Keys are relative paths, anything that could leave the root is refused.
+    fn path(&self, key: &str) -> Result<PathBuf, AppError> {
+        let relative = Path::new(key);
+        if key.is_empty()
+            || !relative
+                .components()
+                .all(|c| matches!(c, Component::Normal(_)))
+        {
+            return Err(AppError::InvalidArgument(format!(
+                "invalid storage key: {}",
+                key
+            )));
+        }
+
+        Ok(self.root.join(relative))
+    }
+}
+
+#[async_trait]
+impl Storage for LocalStorage {
+    async fn put(&self, key: &str, data: Bytes) -> Result<(), AppError> {
+        let path = self.path(key)?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).await?;
+        }
+        fs::write(path, data).await?;
+
+        Ok(())
+    }
+
+    async fn get(&self, key: &str) -> Result<Bytes, AppError> {
+        let data = fs::read(self.path(key)?).await?;
+
+        Ok(data.into())
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), AppError> {
+        match fs::remove_file(self.path(key)?).await {
+            Err(e) if e.kind() != ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Result;
+
+    #[tokio::test]
+    async fn test_local_storage() -> Result<()> {
+        let root = std::env::temp_dir().join(format!("todolist-storage-{}", std::process::id()));
+        let storage = LocalStorage::new(&root);
+
+        storage.put("1/abc", Bytes::from_static(b"doc")).await?;
+        assert_eq!(storage.get("1/abc").await?, Bytes::from_static(b"doc"));
+
+        storage.delete("1/abc").await?;
+        assert!(storage.get("1/abc").await.is_err());
+        // already gone
+        storage.delete("1/abc").await?;
+
+        for key in ["", "../abc", "1/../../abc", "/etc/passwd"] {
+            assert!(matches!(
+                storage.put(key, Bytes::new()).await,
+                Err(AppError::InvalidArgument(_))
+            ));
+        }
+
+        std::fs::remove_dir_all(root)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,66 @@
+use axum::{async_trait, body::Bytes};
+
+use crate::errors::AppError;
+
+mod local;
+
+pub use local::LocalStorage;
+
+/// Where the content of attachments is kept, addressed by an opaque key.
+#[async_trait]
+pub trait Storage: Send + Sync {
+    async fn put(&self, key: &str, data: Bytes) -> Result<(), AppError>;
+
+    async fn get(&self, key: &str) -> Result<Bytes, AppError>;
+
+    /// Deleting a missing key is not an error.
+    async fn delete(&self, key: &str) -> Result<(), AppError>;
+}
+
+/// Content type of a file from its first bytes, the one sent by the client is
+/// not trusted.
+pub fn sniff_content_type(data: &[u8]) -> &'static str {
+    const SIGNATURES: [(&[u8], &str); 8] = [
+        (b"\x89PNG\r\n\x1a\n", "image/png"),
+        (b"\xff\xd8\xff", "image/jpeg"),
+        (b"GIF87a", "image/gif"),
+        (b"GIF89a", "image/gif"),
+        (b"%PDF-", "application/pdf"),
+        (b"PK\x03\x04", "application/zip"),
+        (b"\x1f\x8b", "application/gzip"),
+        (b"\x00asm", "application/wasm"),
+    ];
+
+    if let Some((_, content_type)) = SIGNATURES.iter().find(|(magic, _)| data.starts_with(magic)) {
+        return content_type;
+    }
+    if data.len() >= 12 && &data[..4] == b"RIFF" && &data[8..12] == b"WEBP" {
+        return "image/webp";
+    }
+    if std::str::from_utf8(data).is_ok() {
+        return "text/plain; charset=utf-8";
+    }
+
+    "application/octet-stream"
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sniff_content_type() {
+        assert_eq!(sniff_content_type(b"\x89PNG\r\n\x1a\n...."), "image/png");
+        assert_eq!(sniff_content_type(b"%PDF-1.7\n"), "application/pdf");
+        assert_eq!(sniff_content_type(b"RIFF\0\0\0\0WEBPVP8 "), "image/webp");
+        assert_eq!(
+            sniff_content_type("write doc".as_bytes()),
+            "text/plain; charset=utf-8"
+        );
+        assert_eq!(sniff_content_type(b""), "text/plain; charset=utf-8");
+        assert_eq!(
+            sniff_content_type(b"\xde\xad\xbe\xef"),
+            "application/octet-stream"
+        );
+    }
+}